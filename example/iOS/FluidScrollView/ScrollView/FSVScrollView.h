@@ -73,6 +73,12 @@ NS_SWIFT_NAME(FluidScrollView)
 /// The default value is `NO`.
 @property (nonatomic, assign) BOOL alwaysBounceHorizontal;
 
+/// A Boolean value that determines whether scrolling is disabled in a particular direction.
+///
+/// If this property is `YES` and the user begins dragging in one general direction (horizontally or vertically),
+/// the scroll view disables scrolling in the other direction. The default value is `NO`.
+@property (nonatomic, assign, getter=isDirectionalLockEnabled) BOOL directionalLockEnabled;
+
 /// The minimum point (in content view coordinates) that the content view can be scrolled.
 @property (nonatomic, readonly) CGPoint minimumContentOffset;
 
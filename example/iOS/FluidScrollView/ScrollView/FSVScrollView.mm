@@ -81,8 +81,7 @@ public:
     TouchProxy(UIView<_FSVTouchDelegate> *view)
         : delegate_(view),
           state_(POSSIBLE),
-          velocity_tracker_x_(nullptr),
-          velocity_tracker_y_(nullptr) {}
+          velocity_tracker_(nullptr) {}
     
     State state() const {
         return state_;
@@ -93,9 +92,10 @@ public:
     }
     
     CGPoint velocity() const {
-        const auto vx = fl_velocity_tracker_calculate_velocity(velocity_tracker_x_);
-        const auto vy = fl_velocity_tracker_calculate_velocity(velocity_tracker_y_);
-        return CGPointMake(vx, vy);
+        // A touch that rests before it is lifted does not fling the content.
+        const auto now = static_cast<float>((active_touch_.timestamp - touch_begin_time_) * 1e3);
+        const auto velocity = fl_velocity_tracker_2d_calculate_velocity_at(velocity_tracker_, now);
+        return CGPointMake(velocity.x, velocity.y);
     }
     
     void begin_with_touches(NSSet<UITouch *> *touches) {
@@ -147,11 +147,8 @@ public:
     }
     
     void reset() {
-        if (velocity_tracker_x_ != nullptr) {
-            fl_velocity_tracker_reset(velocity_tracker_x_);
-        }
-        if (velocity_tracker_y_ != nullptr) {
-            fl_velocity_tracker_reset(velocity_tracker_y_);
+        if (velocity_tracker_ != nullptr) {
+            fl_velocity_tracker_2d_reset(velocity_tracker_);
         }
         state_ = POSSIBLE;
         previous_traslation_ = CGPointZero;
@@ -160,11 +157,8 @@ public:
     }
     
     ~TouchProxy() {
-        if (velocity_tracker_x_ != nullptr) {
-            fl_velocity_tracker_free(velocity_tracker_x_);
-        }
-        if (velocity_tracker_y_ != nullptr) {
-            fl_velocity_tracker_free(velocity_tracker_y_);
+        if (velocity_tracker_ != nullptr) {
+            fl_velocity_tracker_2d_free(velocity_tracker_);
         }
     }
     
@@ -172,8 +166,7 @@ private:
     State state_;
     __weak UIView<_FSVTouchDelegate> *delegate_;
     
-    FlVelocityTracker *velocity_tracker_x_;
-    FlVelocityTracker *velocity_tracker_y_;
+    FlVelocityTracker2D *velocity_tracker_;
     
     CGPoint previous_traslation_;
     UITouch *active_touch_;
@@ -189,16 +182,12 @@ private:
     }
     
     void add_current_location() {
-        if (velocity_tracker_x_ == nullptr) {
-            velocity_tracker_x_ = fl_velocity_tracker_new(FL_VELOCITY_TRACKER_RECURRENCE_STRATEGY);
-        }
-        if (velocity_tracker_y_ == nullptr) {
-            velocity_tracker_y_ = fl_velocity_tracker_new(FL_VELOCITY_TRACKER_RECURRENCE_STRATEGY);
+        if (velocity_tracker_ == nullptr) {
+            velocity_tracker_ = fl_velocity_tracker_2d_new(FL_VELOCITY_TRACKER_RECURRENCE_STRATEGY);
         }
         const auto now = static_cast<float>((active_touch_.timestamp - touch_begin_time_) * 1e3);
         const auto trans = translation();
-        fl_velocity_tracker_add_data_point(velocity_tracker_x_, now, static_cast<float>(trans.x));
-        fl_velocity_tracker_add_data_point(velocity_tracker_y_, now, static_cast<float>(trans.y));
+        fl_velocity_tracker_2d_add_data_point(velocity_tracker_, now, FlPoint { static_cast<float>(trans.x), static_cast<float>(trans.y) });
     }
 };
 
@@ -286,6 +275,7 @@ public:
     std::unique_ptr<TouchProxy> _touchProxy;
     // Records the translation of the gesture's first response.
     std::optional<CGPoint> _touchBeganTranslation;
+    FlDirectionalLock *_directionalLock;
     BOOL _isTracking;
     BOOL _isDragging;
     CGPoint _lastContentOffset;
@@ -314,10 +304,15 @@ public:
         _propertiesY = std::make_shared<_ScrollProperties>();
         _ignoredTouches = [NSMutableSet set];
         _impactFeedback = [[UIImpactFeedbackGenerator alloc] initWithStyle:UIImpactFeedbackStyleSoft];
+        _directionalLock = fl_directional_lock_new();
     }
     return self;
 }
 
+- (void)dealloc {
+    fl_directional_lock_free(_directionalLock);
+}
+
 - (void)didMoveToSuperview {
     [super didMoveToSuperview];
     
@@ -438,6 +433,7 @@ public:
                                                         range:viewportSize.height
                                                       inverse:true];
             _touchBeganTranslation = std::nullopt;
+            fl_directional_lock_reset(_directionalLock);
             break;
         case TouchProxy::State::CHANGED: {
             _isTracking = false;
@@ -451,6 +447,14 @@ public:
                 _touchBeganTranslation = translation;
             }
             translation = CGPointSub(translation, *_touchBeganTranslation);
+            if (_directionalLockEnabled) {
+                const auto point = FlPoint { static_cast<float>(translation.x), static_cast<float>(translation.y) };
+                const auto velocity = proxy.velocity();
+                fl_directional_lock_update(_directionalLock, point, FlPoint { static_cast<float>(velocity.x), static_cast<float>(velocity.y) });
+                // The content follows the touch freely until the direction of the gesture is decided.
+                const auto locked = fl_directional_lock_filter_translation(_directionalLock, point);
+                translation = CGPointMake(locked.x, locked.y);
+            }
             const auto x = canHorizontalScroll ? translation.x : 0;
             const auto y = canVerticalScroll ? translation.y : 0;
             auto targetContentOffsetX = _lastContentOffset.x - x;
@@ -476,6 +480,10 @@ public:
             if (fl_velocity_approaching_halt(velocity.x, velocity.y)) {
                 velocity = CGPointZero;
             }
+            if (_directionalLockEnabled) {
+                const auto locked = fl_directional_lock_filter(_directionalLock, FlPoint { static_cast<float>(velocity.x), static_cast<float>(velocity.y) });
+                velocity = CGPointMake(locked.x, locked.y);
+            }
             if (!canHorizontalScroll) velocity.x = 0;
             if (!canVerticalScroll) velocity.y = 0;
             [self _handleEndPanWithVelocity:velocity];
@@ -570,9 +578,17 @@ public:
 
 - (void)_prepareBouncingWithVelocity:(CGFloat)velocity overflowVelocity:(bool)overflowVelocity axis:(UIAxis)axis FSV_DIRECT {
     GET_AXIS_PROPERTIES;
+    const auto overflow = [self _overflowOffsetForAxis:axis];
     // Divide the overflow distance by 100 as the initial bounce velocity of the current position.
-    const auto overV = [self _overflowOffsetForAxis:axis] / 100;
+    const auto overV = overflow / 100;
     if (overflowVelocity) {
+        // The pan velocity is measured before rubber banding, so convert it to the velocity of the content.
+        const auto viewportSize = self.bounds.size;
+        const auto range = axis == UIAxisHorizontal ? viewportSize.width : viewportSize.height;
+        if (std::abs(range) >= CGFLOAT_EPSILON) {
+            const auto pulled = fl_calculate_rubber_band_offset_inv(std::abs(overflow), range);
+            velocity *= fl_calculate_rubber_band_derivative(pulled, range);
+        }
         if (std::signbit(overV) != std::signbit(velocity)) {
             velocity += overV;
         } else {
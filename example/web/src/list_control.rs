@@ -17,7 +17,7 @@ use std::{
     rc::Rc,
 };
 
-use fluid_scroll::{rubber_band, Scroller, SpringBack, VelocityTracker};
+use fluid_scroll::{geometry, rubber_band, Scroller, SpringBack, VelocityTracker2D};
 use num::Zero;
 use web_sys::{Element, MouseEvent, TouchEvent};
 
@@ -74,8 +74,7 @@ pub struct ListControlInner {
 
     touch_adapter: RefCell<EventAdapter>,
     touch_began_time: Cell<f64>,
-    velocity_tracker_x: RefCell<VelocityTracker>,
-    velocity_tracker_y: RefCell<VelocityTracker>,
+    velocity_tracker: RefCell<VelocityTracker2D>,
 
     animator: Rc<Animator>,
     animation_state: RefCell<ListAnimationState>,
@@ -89,8 +88,7 @@ impl ListControl {
             element,
             touch_adapter: RefCell::new(EventAdapter::new()),
             touch_began_time: Cell::new(0.0),
-            velocity_tracker_x: RefCell::new(VelocityTracker::new()),
-            velocity_tracker_y: RefCell::new(VelocityTracker::new()),
+            velocity_tracker: RefCell::new(VelocityTracker2D::new()),
             animation_state: RefCell::new(ListAnimationState::default()),
             animator: Animator::new(),
             content_offset: Cell::new(0.0),
@@ -169,13 +167,11 @@ impl EventAdapterDelegate for ListControlInner {
         let state = sender.state;
         let now = performance_now();
         let point = sender.position;
-        let mut velocity_tracker_x = self.velocity_tracker_x.borrow_mut();
-        let mut velocity_tracker_y = self.velocity_tracker_y.borrow_mut();
+        let mut velocity_tracker = self.velocity_tracker.borrow_mut();
         let mut animation_state = self.animation_state.borrow_mut();
         match state {
             TouchState::Began => {
-                velocity_tracker_x.reset();
-                velocity_tracker_y.reset();
+                velocity_tracker.reset();
 
                 self.touch_began_time.set(now);
                 animation_state.type_ = ListAnimationType::None;
@@ -187,24 +183,32 @@ impl EventAdapterDelegate for ListControlInner {
             TouchState::Changed => {
                 let began_time = self.touch_began_time.get();
                 let elapsed = now - began_time;
-                velocity_tracker_x.add_data_point(elapsed as f32, point.x as f32);
-                velocity_tracker_y.add_data_point(elapsed as f32, point.y as f32);
+                velocity_tracker.add_data_point(
+                    elapsed as f32,
+                    geometry::Point::new(point.x as f32, point.y as f32),
+                );
                 let translation = sender.translation;
                 let offset = animation_state.last_offset - translation.y as f32;
                 self.set_content_offset(self.rubber_band_for_offset(offset, false));
             }
             TouchState::Ended | TouchState::Cancelled => {
-                let mut velocity = -Point::new(
-                    velocity_tracker_x.calculate(),
-                    velocity_tracker_y.calculate(),
-                );
-                if VelocityTracker::approaching_halt(velocity.x, velocity.y) {
-                    velocity = Point::zero();
-                }
+                let tracked_velocity = velocity_tracker.calculate();
+                let mut velocity = if VelocityTracker2D::approaching_halt(tracked_velocity) {
+                    Point::zero()
+                } else {
+                    -Point::new(tracked_velocity.x, tracked_velocity.y)
+                };
 
                 let overflow = self.overflow_offset();
                 if overflow != 0.0 {
                     // When released, the content offset has exceeded the boundary.
+                    // The touch velocity is measured before rubber banding, so convert it to
+                    // the velocity of the content.
+                    let range = self.element_height();
+                    if range.abs() >= std::f32::EPSILON {
+                        let pulled = rubber_band::calculate_offset_inv(overflow.abs(), range);
+                        velocity.y *= rubber_band::calculate_derivative(pulled, range);
+                    }
                     let overflow_velocity = -overflow / 100.0;
                     // If two velocities are in opposite directions, add the two velocities.
                     if overflow_velocity.is_sign_negative() != velocity.y.is_sign_negative() {
@@ -346,7 +350,7 @@ impl Animatable for ListControlInner {
             }
             ListAnimationType::SpringBack => {
                 if let Some(value) = animation_state.spring_back.value(elapsed) {
-                    self.set_content_offset(value + animation_state.spring_target);
+                    self.set_content_offset(value.offset + animation_state.spring_target);
                 } else {
                     animation_state.type_ = ListAnimationType::None;
                     animation_state.began_time = None;
@@ -16,6 +16,8 @@
 #define __SCROLL_PHYSICS_H
 
 #include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
 
 #ifdef __cplusplus
 extern "C" {
@@ -38,13 +40,19 @@ void fl_scroller_set_deceleration_rate(FlScroller *scroller,
 
 void fl_scroller_fling(FlScroller *scroller, float velocity);
 
+bool fl_scroller_fling_to(FlScroller *scroller, float velocity, float target);
+
 FlScrollerValue fl_scroller_value(FlScroller *scroller, float time,
                                   bool *out_stop);
 
+float fl_scroller_final_offset(FlScroller *scroller);
+
+float fl_scroller_duration(FlScroller *scroller);
+
 void fl_scroller_reset(FlScroller *scroller);
 
 typedef struct {
-  char padding[16];
+  char padding[24];
 } FlSpringBack;
 
 void fl_spring_back_init(FlSpringBack *spring_back);
@@ -56,26 +64,98 @@ void fl_spring_back_absorb_with_response(FlSpringBack *spring_back,
                                          float velocity, float distance,
                                          float response);
 
+void fl_spring_back_retarget(FlSpringBack *spring_back, float time,
+                             float target);
+
 float fl_spring_back_value(FlSpringBack *spring_back, float time,
                            bool *out_stop);
 
+/// Returns the velocity in points per millisecond.
+float fl_spring_back_velocity(FlSpringBack *spring_back, float time);
+
 void fl_spring_back_reset(FlSpringBack *spring_back);
 
+typedef struct __FlSpring FlSpring;
+
+FlSpring *fl_spring_new(float response, float damping_ratio);
+
+FlSpring *fl_spring_new_with_mass_stiffness_damping(float mass, float stiffness,
+                                                   float damping);
+
+void fl_spring_free(FlSpring *spring);
+
+void fl_spring_start(FlSpring *spring, float value, float target,
+                     float velocity);
+
+void fl_spring_retarget(FlSpring *spring, float time, float target);
+
+/// Returns the target value when the spring has come to rest. `out_stop` may be
+/// NULL.
+float fl_spring_value(FlSpring *spring, float time, bool *out_stop);
+
+float fl_spring_velocity(FlSpring *spring, float time);
+
+void fl_spring_reset(FlSpring *spring);
+
 float fl_calculate_rubber_band_offset(float offset, float range);
 
 float fl_calculate_rubber_band_offset_inv(float offset, float range);
 
+/// Returns the ratio of the displayed velocity to the velocity that the content
+/// is pulled with, at the given pulled offset beyond the edge.
+float fl_calculate_rubber_band_derivative(float offset, float range);
+
+/// Returns the ratio of the pulled velocity to the displayed velocity, at the
+/// given displayed offset beyond the edge.
+float fl_calculate_rubber_band_derivative_inv(float offset, float range);
+
+enum FlRubberBandCurve {
+  FL_RUBBER_BAND_RECIPROCAL_CURVE = 0,
+  FL_RUBBER_BAND_LOGARITHMIC_CURVE = 1,
+  FL_RUBBER_BAND_EXPONENTIAL_CURVE = 2,
+};
+
+/// Maps an offset with a custom rubber band. The default coefficient is 0.55.
+/// An unknown curve falls back to FL_RUBBER_BAND_RECIPROCAL_CURVE.
+float fl_calculate_rubber_band_offset_with_curve(float offset, float range,
+                                                 float coefficient,
+                                                 FlRubberBandCurve curve);
+
+float fl_calculate_rubber_band_offset_inv_with_curve(float offset, float range,
+                                                     float coefficient,
+                                                     FlRubberBandCurve curve);
+
+float fl_calculate_rubber_band_derivative_with_curve(float offset, float range,
+                                                     float coefficient,
+                                                     FlRubberBandCurve curve);
+
+float fl_calculate_rubber_band_derivative_inv_with_curve(
+    float offset, float range, float coefficient, FlRubberBandCurve curve);
+
 typedef struct __FlVelocityTracker FlVelocityTracker;
 
 enum FlVelocityTrackerStrategy {
   FL_VELOCITY_TRACKER_RECURRENCE_STRATEGY = 0,
   FL_VELOCITY_TRACKER_LSQ2_STRATEGY = 1,
+  FL_VELOCITY_TRACKER_LSQ1_STRATEGY = 2,
+  FL_VELOCITY_TRACKER_LSQ3_STRATEGY = 3,
+  FL_VELOCITY_TRACKER_WLSQ2_DELTA_STRATEGY = 4,
+  FL_VELOCITY_TRACKER_WLSQ2_CENTRAL_STRATEGY = 5,
+  FL_VELOCITY_TRACKER_WLSQ2_RECENT_STRATEGY = 6,
+  FL_VELOCITY_TRACKER_IMPULSE_STRATEGY = 7,
 };
 
 FlVelocityTracker *fl_velocity_tracker_new(FlVelocityTrackerStrategy strategy);
 
 FlVelocityTracker *fl_velocity_tracker_new_default(void);
 
+/// Creates a velocity tracker that keeps at most `history_size` samples (up to
+/// 64), and ignores the samples older than `horizon` milliseconds or preceding
+/// a pause longer than `pointer_stopped_threshold` milliseconds.
+FlVelocityTracker *fl_velocity_tracker_new_with_config(
+    FlVelocityTrackerStrategy strategy, size_t history_size, float horizon,
+    float pointer_stopped_threshold);
+
 void fl_velocity_tracker_free(FlVelocityTracker *velocity_tracker);
 
 void fl_velocity_tracker_add_data_point(FlVelocityTracker *velocity_tracker,
@@ -84,10 +164,359 @@ void fl_velocity_tracker_add_data_point(FlVelocityTracker *velocity_tracker,
 float fl_velocity_tracker_calculate_velocity(
     FlVelocityTracker *velocity_tracker);
 
+/// Computes the velocity at the given time, such as when the pointer is lifted.
+/// It is zero if the pointer has rested for longer than the pointer stopped
+/// threshold before `time`.
+float fl_velocity_tracker_calculate_velocity_at(
+    FlVelocityTracker *velocity_tracker, float time);
+
 void fl_velocity_tracker_reset(FlVelocityTracker *velocity_tracker);
 
 bool fl_velocity_approaching_halt(float vx, float vy);
 
+typedef struct {
+  float x;
+  float y;
+} FlPoint;
+
+typedef struct {
+  float width;
+  float height;
+} FlSize;
+
+typedef struct {
+  float leading;
+  float trailing;
+} FlAxisInsets;
+
+typedef struct {
+  float top;
+  float left;
+  float bottom;
+  float right;
+} FlEdgeInsets;
+
+typedef struct __FlVelocityTracker2D FlVelocityTracker2D;
+
+FlVelocityTracker2D *
+fl_velocity_tracker_2d_new(FlVelocityTrackerStrategy strategy);
+
+FlVelocityTracker2D *fl_velocity_tracker_2d_new_with_config(
+    FlVelocityTrackerStrategy strategy, size_t history_size, float horizon,
+    float pointer_stopped_threshold);
+
+void fl_velocity_tracker_2d_free(FlVelocityTracker2D *velocity_tracker);
+
+void fl_velocity_tracker_2d_add_data_point(
+    FlVelocityTracker2D *velocity_tracker, float time, FlPoint position);
+
+FlPoint fl_velocity_tracker_2d_calculate_velocity(
+    FlVelocityTracker2D *velocity_tracker);
+
+/// Computes the velocity vector at the given time, like
+/// `fl_velocity_tracker_calculate_velocity_at`.
+FlPoint fl_velocity_tracker_2d_calculate_velocity_at(
+    FlVelocityTracker2D *velocity_tracker, float time);
+
+void fl_velocity_tracker_2d_reset(FlVelocityTracker2D *velocity_tracker);
+
+typedef struct __FlDirectionalLock FlDirectionalLock;
+
+FlDirectionalLock *fl_directional_lock_new(void);
+
+void fl_directional_lock_free(FlDirectionalLock *directional_lock);
+
+/// Sets the angle in radians between the gesture and an axis, within which the
+/// gesture is locked to that axis.
+void fl_directional_lock_set_angle_tolerance(FlDirectionalLock *directional_lock,
+                                             float angle_tolerance);
+
+/// Updates the lock with the translation and the velocity of the gesture, and
+/// returns true once the direction is decided.
+bool fl_directional_lock_update(FlDirectionalLock *directional_lock,
+                                FlPoint translation, FlPoint velocity);
+
+/// Returns the translation that the content should follow. Once the direction
+/// is decided, the translation along the other axis stays where it was.
+FlPoint fl_directional_lock_filter_translation(
+    FlDirectionalLock *directional_lock, FlPoint translation);
+
+/// Suppresses the component of `point` along the axis that the gesture is not
+/// allowed to scroll in.
+FlPoint fl_directional_lock_filter(FlDirectionalLock *directional_lock,
+                                   FlPoint point);
+
+void fl_directional_lock_reset(FlDirectionalLock *directional_lock);
+
+typedef struct __FlScrollController FlScrollController;
+
+enum FlScrollPhase {
+  FL_SCROLL_PHASE_IDLE = 0,
+  FL_SCROLL_PHASE_TRACKING = 1,
+  FL_SCROLL_PHASE_DRAGGING = 2,
+  FL_SCROLL_PHASE_DECELERATING = 3,
+  FL_SCROLL_PHASE_BOUNCING = 4,
+  FL_SCROLL_PHASE_ANIMATING = 5,
+};
+
+/// Called once when a programmatic scroll animation ends. `finished` is false if
+/// the animation was interrupted.
+typedef void (*FlScrollCompletion)(void *context, bool finished);
+
+FlScrollController *fl_scroll_controller_new(void);
+
+void fl_scroll_controller_free(FlScrollController *scroll_controller);
+
+void fl_scroll_controller_set_content_length(
+    FlScrollController *scroll_controller, float content_length);
+
+void fl_scroll_controller_set_viewport_length(
+    FlScrollController *scroll_controller, float viewport_length);
+
+void fl_scroll_controller_set_content_inset(
+    FlScrollController *scroll_controller, FlAxisInsets content_inset);
+
+/// Sets the insets covered by system bars, like `safeAreaInsets` of `UIView`.
+void fl_scroll_controller_set_safe_area_inset(
+    FlScrollController *scroll_controller, FlAxisInsets safe_area_inset);
+
+/// Sets an extra inset managed by the front-end, e.g. for a refresh control.
+void fl_scroll_controller_set_additional_inset(
+    FlScrollController *scroll_controller, FlAxisInsets additional_inset);
+
+/// Returns the sum of the content, safe area and additional insets.
+FlAxisInsets
+fl_scroll_controller_adjusted_content_inset(FlScrollController *scroll_controller);
+
+void fl_scroll_controller_set_deceleration_rate(
+    FlScrollController *scroll_controller, float deceleration_rate);
+
+void fl_scroll_controller_set_bounce_response(
+    FlScrollController *scroll_controller, float bounce_response);
+
+void fl_scroll_controller_set_rubber_band(FlScrollController *scroll_controller,
+                                          float coefficient,
+                                          FlRubberBandCurve curve);
+
+float fl_scroll_controller_min_offset(FlScrollController *scroll_controller);
+
+float fl_scroll_controller_max_offset(FlScrollController *scroll_controller);
+
+float fl_scroll_controller_offset(FlScrollController *scroll_controller);
+
+void fl_scroll_controller_set_offset(FlScrollController *scroll_controller,
+                                     float offset);
+
+/// Keeps the visible content in place when content of `delta` length is inserted
+/// at `at`, or removed if `delta` is negative.
+void fl_scroll_controller_adjust_for_content_inserted(
+    FlScrollController *scroll_controller, float at, float delta);
+
+/// Scales the content length by `ratio` around `focal`, a position in the
+/// viewport, keeping the content under it in place.
+void fl_scroll_controller_scale_content(FlScrollController *scroll_controller,
+                                        float ratio, float focal);
+
+/// Animates the content to the given offset. `completion` may be NULL.
+void fl_scroll_controller_animate_to(FlScrollController *scroll_controller,
+                                     float now, float offset,
+                                     FlScrollCompletion completion,
+                                     void *context);
+
+float fl_scroll_controller_velocity(FlScrollController *scroll_controller);
+
+enum FlScrollPhase
+fl_scroll_controller_phase(FlScrollController *scroll_controller);
+
+void fl_scroll_controller_set_scroll_enabled(
+    FlScrollController *scroll_controller, bool scroll_enabled);
+
+void fl_scroll_controller_set_bounces(FlScrollController *scroll_controller,
+                                      bool bounces);
+
+void fl_scroll_controller_set_always_bounce(
+    FlScrollController *scroll_controller, bool always_bounce);
+
+/// Enables paging with the given page size, or disables it if the page size is
+/// not positive.
+void fl_scroll_controller_set_page_size(FlScrollController *scroll_controller,
+                                        float page_size);
+
+/// Sets the offsets that the content is allowed to come to rest on, or removes
+/// them if `count` is zero.
+void fl_scroll_controller_set_snap_points(FlScrollController *scroll_controller,
+                                          const float *offsets, size_t count);
+
+bool fl_scroll_controller_can_scroll(FlScrollController *scroll_controller);
+
+void fl_scroll_controller_touch_began(FlScrollController *scroll_controller,
+                                      float time, float position);
+
+void fl_scroll_controller_touch_moved(FlScrollController *scroll_controller,
+                                      float time, float position);
+
+void fl_scroll_controller_touch_ended(FlScrollController *scroll_controller,
+                                      float time);
+
+float fl_scroll_controller_tick(FlScrollController *scroll_controller,
+                                float now);
+
+enum FlScrollEventType {
+  FL_SCROLL_EVENT_WILL_BEGIN_DRAGGING = 0,
+  FL_SCROLL_EVENT_DID_SCROLL = 1,
+  FL_SCROLL_EVENT_WILL_END_DRAGGING = 2,
+  FL_SCROLL_EVENT_DID_END_DECELERATING = 3,
+  FL_SCROLL_EVENT_DID_END_SCROLLING_ANIMATION = 4,
+  FL_SCROLL_EVENT_DID_BOUNCE = 5,
+};
+
+/// Receives the scroll events of a controller. `value` is the offset of
+/// `DID_SCROLL`, or the velocity of `WILL_END_DRAGGING` and `DID_BOUNCE`.
+/// `target` points to the resting offset of `WILL_END_DRAGGING`, which can be
+/// changed by the observer, and is NULL for the other events.
+///
+/// The observer is called while the controller is being updated. It must not
+/// call any fl_scroll_controller_* function with the same controller, which is
+/// undefined behavior. Use the values of the event, or defer the work until the
+/// call that sent the event has returned.
+typedef void (*FlScrollObserver)(void *context, FlScrollEventType event,
+                                 float value, float *target);
+
+/// Returns the identifier to remove the observer with.
+uint64_t fl_scroll_controller_add_observer(
+    FlScrollController *scroll_controller, FlScrollObserver observer,
+    void *context);
+
+bool fl_scroll_controller_remove_observer(FlScrollController *scroll_controller,
+                                          uint64_t observer_id);
+
+typedef struct __FlScrollController2D FlScrollController2D;
+
+FlScrollController2D *fl_scroll_controller_2d_new(void);
+
+void fl_scroll_controller_2d_free(FlScrollController2D *scroll_controller);
+
+/// The returned controller is owned by the two-dimensional controller and must not be freed.
+FlScrollController *
+fl_scroll_controller_2d_horizontal(FlScrollController2D *scroll_controller);
+
+/// The returned controller is owned by the two-dimensional controller and must not be freed.
+FlScrollController *
+fl_scroll_controller_2d_vertical(FlScrollController2D *scroll_controller);
+
+void fl_scroll_controller_2d_set_content_size(
+    FlScrollController2D *scroll_controller, FlSize content_size);
+
+void fl_scroll_controller_2d_set_viewport_size(
+    FlScrollController2D *scroll_controller, FlSize viewport_size);
+
+void fl_scroll_controller_2d_set_content_inset(
+    FlScrollController2D *scroll_controller, FlEdgeInsets content_inset);
+
+void fl_scroll_controller_2d_set_safe_area_inset(
+    FlScrollController2D *scroll_controller, FlEdgeInsets safe_area_inset);
+
+void fl_scroll_controller_2d_set_additional_inset(
+    FlScrollController2D *scroll_controller, FlEdgeInsets additional_inset);
+
+FlEdgeInsets fl_scroll_controller_2d_adjusted_content_inset(
+    FlScrollController2D *scroll_controller);
+
+void fl_scroll_controller_2d_set_directional_lock_enabled(
+    FlScrollController2D *scroll_controller, bool enabled);
+
+FlPoint
+fl_scroll_controller_2d_min_offset(FlScrollController2D *scroll_controller);
+
+FlPoint
+fl_scroll_controller_2d_max_offset(FlScrollController2D *scroll_controller);
+
+FlPoint fl_scroll_controller_2d_offset(FlScrollController2D *scroll_controller);
+
+void fl_scroll_controller_2d_set_offset(FlScrollController2D *scroll_controller,
+                                        FlPoint offset);
+
+/// Scales the content size by `ratio` around `focal_point`, a point in the
+/// viewport, keeping the content under it in place.
+void fl_scroll_controller_2d_scale_content(
+    FlScrollController2D *scroll_controller, float ratio, FlPoint focal_point);
+
+/// Animates the content to the given offset. `completion` may be NULL.
+void fl_scroll_controller_2d_animate_to(
+    FlScrollController2D *scroll_controller, float now, FlPoint offset,
+    FlScrollCompletion completion, void *context);
+
+FlPoint
+fl_scroll_controller_2d_velocity(FlScrollController2D *scroll_controller);
+
+enum FlScrollPhase
+fl_scroll_controller_2d_phase(FlScrollController2D *scroll_controller);
+
+void fl_scroll_controller_2d_touch_began(
+    FlScrollController2D *scroll_controller, float time, FlPoint position);
+
+void fl_scroll_controller_2d_touch_moved(
+    FlScrollController2D *scroll_controller, float time, FlPoint position);
+
+void fl_scroll_controller_2d_touch_ended(
+    FlScrollController2D *scroll_controller, float time);
+
+FlPoint fl_scroll_controller_2d_tick(FlScrollController2D *scroll_controller,
+                                     float now);
+
+typedef struct __FlZoomController FlZoomController;
+
+enum FlZoomPhase {
+  FL_ZOOM_PHASE_IDLE = 0,
+  FL_ZOOM_PHASE_PINCHING = 1,
+  FL_ZOOM_PHASE_BOUNCING = 2,
+  FL_ZOOM_PHASE_ANIMATING = 3,
+};
+
+FlZoomController *fl_zoom_controller_new(void);
+
+void fl_zoom_controller_free(FlZoomController *zoom_controller);
+
+void fl_zoom_controller_set_min_scale(FlZoomController *zoom_controller,
+                                      float min_scale);
+
+void fl_zoom_controller_set_max_scale(FlZoomController *zoom_controller,
+                                      float max_scale);
+
+void fl_zoom_controller_set_bounces_zoom(FlZoomController *zoom_controller,
+                                         bool bounces_zoom);
+
+float fl_zoom_controller_scale(FlZoomController *zoom_controller);
+
+void fl_zoom_controller_set_scale(FlZoomController *zoom_controller,
+                                  float scale);
+
+/// Returns the point in the viewport that stays in place while zooming.
+FlPoint fl_zoom_controller_focal_point(FlZoomController *zoom_controller);
+
+enum FlZoomPhase fl_zoom_controller_phase(FlZoomController *zoom_controller);
+
+void fl_zoom_controller_pinch_began(FlZoomController *zoom_controller,
+                                    float time, FlPoint focal_point);
+
+/// `pinch_scale` is the scale of the gesture relative to when it began.
+void fl_zoom_controller_pinch_changed(FlZoomController *zoom_controller,
+                                      float time, float pinch_scale,
+                                      FlPoint focal_point);
+
+void fl_zoom_controller_pinch_ended(FlZoomController *zoom_controller,
+                                    float time);
+
+void fl_zoom_controller_animate_to(FlZoomController *zoom_controller,
+                                   float now, float scale, FlPoint focal_point);
+
+/// Zooms in to the maximum scale, or back out to the minimum scale if the
+/// content is already zoomed in.
+void fl_zoom_controller_double_tap(FlZoomController *zoom_controller,
+                                   float now, FlPoint focal_point);
+
+float fl_zoom_controller_tick(FlZoomController *zoom_controller, float now);
+
 #ifdef __cplusplus
 }
 #endif
@@ -12,5 +12,5 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub const VELOCITY_THRESHOLD: f32 = 1e-2;
-pub const VALUE_THRESHOLD: f32 = 0.1;
+pub const VELOCITY_THRESHOLD: f64 = 1e-2;
+pub const VALUE_THRESHOLD: f64 = 0.1;
@@ -0,0 +1,244 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::f32::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_8};
+
+use crate::{
+    geometry::Point,
+    math::{atan2, hypot},
+};
+
+/// The default angle in radians between the gesture and an axis, within which the gesture
+/// is locked to that axis.
+pub const DEFAULT_ANGLE_TOLERANCE: f32 = FRAC_PI_8;
+
+/// The default distance in points that the touch has to travel before the direction is decided.
+pub const DEFAULT_LOCK_DISTANCE: f32 = 10.0_f32;
+
+/// The direction that a gesture is allowed to scroll in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LockDirection {
+    /// The gesture only scrolls the content horizontally.
+    Horizontal,
+    /// The gesture only scrolls the content vertically.
+    Vertical,
+    /// The gesture moves diagonally, and scrolls the content along both axes.
+    Free,
+}
+
+impl LockDirection {
+    /// Returns the direction of a gesture with the given translation.
+    ///
+    /// The gesture is locked to an axis if the angle between the translation and that axis
+    /// is not greater than `angle_tolerance`, which is clamped to 45 degrees.
+    pub fn from_translation(translation: Point, angle_tolerance: f32) -> Self {
+        let tolerance = angle_tolerance.clamp(0.0, FRAC_PI_4);
+        // The angle from the horizontal axis, in [0, PI / 2].
+        let angle = atan2(translation.y.abs(), translation.x.abs());
+        if angle <= tolerance {
+            Self::Horizontal
+        } else if angle >= FRAC_PI_2 - tolerance {
+            Self::Vertical
+        } else {
+            Self::Free
+        }
+    }
+
+    /// Suppresses the component of `point` along the axis that the gesture is not allowed
+    /// to scroll in.
+    pub fn filter(&self, point: Point) -> Point {
+        match self {
+            Self::Horizontal => Point::new(point.x, 0.0),
+            Self::Vertical => Point::new(0.0, point.y),
+            Self::Free => point,
+        }
+    }
+}
+
+/// A behavior that locks a two-dimensional gesture to one axis, like `isDirectionalLockEnabled`
+/// of `UIScrollView`.
+///
+/// The direction is decided once from the first points of the gesture, when the touch has
+/// travelled `lock_distance`, and stays the same until the lock is reset by the next touch.
+/// Until then, the content follows the touch along both axes. Afterwards, the translation
+/// along the other axis stays where it was when the direction was decided, and the release
+/// velocity along it is suppressed, so the content neither drifts nor flings sideways.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DirectionalLock {
+    angle_tolerance: f32,
+    lock_distance: f32,
+    direction: Option<LockDirection>,
+    // The translation of the touch when the direction was decided.
+    lock_translation: Point,
+}
+
+impl DirectionalLock {
+    pub fn new() -> Self {
+        Self {
+            angle_tolerance: DEFAULT_ANGLE_TOLERANCE,
+            lock_distance: DEFAULT_LOCK_DISTANCE,
+            direction: None,
+            lock_translation: Point::ZERO,
+        }
+    }
+
+    /// The angle in radians between the gesture and an axis, within which the gesture
+    /// is locked to that axis.
+    pub fn angle_tolerance(&self) -> f32 {
+        self.angle_tolerance
+    }
+
+    pub fn set_angle_tolerance(&mut self, angle_tolerance: f32) {
+        self.angle_tolerance = angle_tolerance.clamp(0.0, FRAC_PI_4);
+    }
+
+    /// The distance in points that the touch has to travel before the direction is decided.
+    pub fn lock_distance(&self) -> f32 {
+        self.lock_distance
+    }
+
+    pub fn set_lock_distance(&mut self, lock_distance: f32) {
+        self.lock_distance = lock_distance.max(0.0);
+    }
+
+    /// The direction of the current gesture, or `None` if it has not been decided yet.
+    pub fn direction(&self) -> Option<LockDirection> {
+        self.direction
+    }
+
+    /// Updates the lock with the translation of the touch since the gesture began and its
+    /// current velocity, and returns the direction once it is decided.
+    ///
+    /// The translation alone cannot tell a straight gesture from one that is already turning,
+    /// so the gesture is only locked to an axis if its velocity points along that axis too.
+    /// Otherwise it is left free. A zero velocity, as at the first move, does not count.
+    pub fn update(&mut self, translation: Point, velocity: Point) -> Option<LockDirection> {
+        if self.direction.is_none() && hypot(translation.x, translation.y) >= self.lock_distance {
+            let mut direction = LockDirection::from_translation(translation, self.angle_tolerance);
+            if velocity != Point::ZERO
+                && LockDirection::from_translation(velocity, self.angle_tolerance) != direction
+            {
+                direction = LockDirection::Free;
+            }
+            self.direction = Some(direction);
+            self.lock_translation = translation;
+        }
+        self.direction
+    }
+
+    /// Returns the translation that the content should follow.
+    ///
+    /// Once the direction is decided, the component along the axis that the gesture is not
+    /// allowed to scroll in stays where it was at that moment, so the content does not jump
+    /// back. The translation is returned unchanged if the direction has not been decided.
+    pub fn filter_translation(&self, translation: Point) -> Point {
+        let Some(direction) = self.direction else {
+            return translation;
+        };
+        let change = direction.filter(Point::new(
+            translation.x - self.lock_translation.x,
+            translation.y - self.lock_translation.y,
+        ));
+        Point::new(
+            self.lock_translation.x + change.x,
+            self.lock_translation.y + change.y,
+        )
+    }
+
+    /// Suppresses the component of `point` along the axis that the gesture is not allowed
+    /// to scroll in. The point is returned unchanged if the direction has not been decided.
+    pub fn filter(&self, point: Point) -> Point {
+        match self.direction {
+            Some(direction) => direction.filter(point),
+            None => point,
+        }
+    }
+
+    /// Clears the direction, so that it is decided again by the next gesture.
+    pub fn reset(&mut self) {
+        self.direction = None;
+        self.lock_translation = Point::ZERO;
+    }
+}
+
+impl Default for DirectionalLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Point;
+
+    use super::{DirectionalLock, LockDirection, DEFAULT_ANGLE_TOLERANCE};
+
+    #[test]
+    fn test_from_translation() {
+        let direction =
+            |x, y| LockDirection::from_translation(Point::new(x, y), DEFAULT_ANGLE_TOLERANCE);
+        assert_eq!(direction(20.0, 3.0), LockDirection::Horizontal);
+        assert_eq!(direction(-20.0, -3.0), LockDirection::Horizontal);
+        assert_eq!(direction(-2.0, 30.0), LockDirection::Vertical);
+        assert_eq!(direction(10.0, -10.0), LockDirection::Free);
+        // A tolerance of 45 degrees never leaves the gesture free.
+        assert_eq!(
+            LockDirection::from_translation(Point::new(10.0, 9.0), 1.0),
+            LockDirection::Horizontal
+        );
+    }
+
+    #[test]
+    fn test_lock_is_decided_once() {
+        let mut lock = DirectionalLock::new();
+        assert_eq!(lock.update(Point::new(1.0, 3.0), Point::ZERO), None);
+        assert_eq!(
+            lock.filter_translation(Point::new(1.0, 3.0)),
+            Point::new(1.0, 3.0)
+        );
+        assert_eq!(
+            lock.update(Point::new(2.0, 12.0), Point::new(0.1, 0.8)),
+            Some(LockDirection::Vertical)
+        );
+        // The gesture stays locked even if it turns sideways afterwards.
+        assert_eq!(
+            lock.update(Point::new(80.0, 20.0), Point::new(2.0, 0.1)),
+            Some(LockDirection::Vertical)
+        );
+        assert_eq!(lock.filter(Point::new(1.5, -2.0)), Point::new(0.0, -2.0));
+        // The horizontal translation stays where it was when the direction was decided.
+        assert_eq!(
+            lock.filter_translation(Point::new(80.0, 20.0)),
+            Point::new(2.0, 20.0)
+        );
+
+        lock.reset();
+        assert_eq!(lock.direction(), None);
+        assert_eq!(
+            lock.update(Point::new(80.0, 20.0), Point::ZERO),
+            Some(LockDirection::Horizontal)
+        );
+    }
+
+    #[test]
+    fn test_turning_gesture_is_not_locked() {
+        let mut lock = DirectionalLock::new();
+        // The touch has travelled vertically, but is already moving diagonally.
+        assert_eq!(
+            lock.update(Point::new(2.0, 12.0), Point::new(0.6, 0.6)),
+            Some(LockDirection::Free)
+        );
+        assert_eq!(lock.filter(Point::new(1.5, -2.0)), Point::new(1.5, -2.0));
+    }
+}
@@ -12,12 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::ffi::{c_char, c_void};
+use std::ffi::{c_char, c_int, c_void};
 
-use crate::rubber_band;
+use crate::directional_lock::*;
+use crate::geometry::*;
+use crate::paging::*;
+use crate::rubber_band::{self, *};
+use crate::scroll_controller::*;
+use crate::scroll_controller_2d::*;
 use crate::scroller::*;
+use crate::snapping::*;
+use crate::spring::*;
 use crate::spring_back::*;
 use crate::velocity_tracker::*;
+use crate::zoom::*;
+
+/// A C callback that is called once with its context when a scroll animation ends.
+type FlScrollCompletion = Option<extern "C" fn(context: *mut c_void, finished: bool)>;
+
+fn scroll_completion(completion: FlScrollCompletion, context: *mut c_void) -> impl FnOnce(bool) {
+    move |finished| {
+        if let Some(completion) = completion {
+            completion(context, finished);
+        }
+    }
+}
 
 #[no_mangle]
 pub extern "C" fn fl_scroller_init(scroller_ptr: *mut c_void, deceleration_rate: f32) {
@@ -47,6 +66,16 @@ pub extern "C" fn fl_scroller_fling(scroller_ptr: *mut c_void, velocity: f32) {
     scroller.fling(velocity);
 }
 
+#[no_mangle]
+pub extern "C" fn fl_scroller_fling_to(
+    scroller_ptr: *mut c_void,
+    velocity: f32,
+    target: f32,
+) -> bool {
+    let scroller = unsafe { &mut *(scroller_ptr as *mut Scroller) };
+    scroller.fling_to(velocity, target)
+}
+
 #[no_mangle]
 pub extern "C" fn fl_scroller_value(
     scroller_ptr: *mut c_void,
@@ -69,6 +98,18 @@ pub extern "C" fn fl_scroller_value(
     return value;
 }
 
+#[no_mangle]
+pub extern "C" fn fl_scroller_final_offset(scroller_ptr: *mut c_void) -> f32 {
+    let scroller = unsafe { &*(scroller_ptr as *mut Scroller) };
+    scroller.final_offset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroller_duration(scroller_ptr: *mut c_void) -> f32 {
+    let scroller = unsafe { &*(scroller_ptr as *mut Scroller) };
+    scroller.duration()
+}
+
 #[no_mangle]
 pub extern "C" fn fl_scroller_reset(scroller_ptr: *mut c_void) {
     let scroller = unsafe { &mut *(scroller_ptr as *mut Scroller) };
@@ -104,6 +145,12 @@ pub extern "C" fn fl_spring_back_absorb_with_response(
     spring_back.absorb_with_response(velocity, distance, response);
 }
 
+#[no_mangle]
+pub extern "C" fn fl_spring_back_retarget(spring_back_ptr: *mut c_void, time: f32, target: f32) {
+    let spring_back = unsafe { &mut *(spring_back_ptr as *mut SpringBack) };
+    spring_back.retarget(time, target);
+}
+
 #[no_mangle]
 pub extern "C" fn fl_spring_back_value(
     spring_back_ptr: *mut c_void,
@@ -120,7 +167,13 @@ pub extern "C" fn fl_spring_back_value(
     unsafe {
         *out_stop = 0;
     }
-    return value;
+    return value.offset;
+}
+
+#[no_mangle]
+pub extern "C" fn fl_spring_back_velocity(spring_back_ptr: *mut c_void, time: f32) -> f32 {
+    let spring_back = unsafe { &*(spring_back_ptr as *mut SpringBack) };
+    spring_back.velocity(time)
 }
 
 #[no_mangle]
@@ -129,6 +182,77 @@ pub extern "C" fn fl_spring_back_reset(spring_back_ptr: *mut c_void) {
     spring_back.reset();
 }
 
+#[no_mangle]
+pub extern "C" fn fl_spring_new(response: f32, damping_ratio: f32) -> *mut c_void {
+    let spring = Box::new(Spring::new(response, damping_ratio));
+    Box::into_raw(spring) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_spring_new_with_mass_stiffness_damping(
+    mass: f32,
+    stiffness: f32,
+    damping: f32,
+) -> *mut c_void {
+    let spring = Box::new(Spring::with_mass_stiffness_damping(
+        mass, stiffness, damping,
+    ));
+    Box::into_raw(spring) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_spring_free(spring_ptr: *mut c_void) {
+    let spring = unsafe { Box::from_raw(spring_ptr as *mut Spring) };
+    drop(spring)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_spring_start(spring_ptr: *mut c_void, value: f32, target: f32, velocity: f32) {
+    let spring = unsafe { &mut *(spring_ptr as *mut Spring) };
+    spring.start(value, target, velocity);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_spring_retarget(spring_ptr: *mut c_void, time: f32, target: f32) {
+    let spring = unsafe { &mut *(spring_ptr as *mut Spring) };
+    spring.retarget(time, target);
+}
+
+/// Returns the value of the spring at the given time, and writes whether it has come to rest
+/// to `out_stop` unless it is null.
+///
+/// # Safety
+///
+/// `spring_ptr` must be a spring created by `fl_spring_new`, and `out_stop` must be null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn fl_spring_value(
+    spring_ptr: *mut c_void,
+    time: f32,
+    out_stop: *mut c_char,
+) -> f32 {
+    let spring = unsafe { &*(spring_ptr as *mut Spring) };
+    let value = spring.value(time);
+    if !out_stop.is_null() {
+        unsafe {
+            *out_stop = value.is_none() as c_char;
+        }
+    }
+    value.unwrap_or_else(|| spring.target())
+}
+
+#[no_mangle]
+pub extern "C" fn fl_spring_velocity(spring_ptr: *mut c_void, time: f32) -> f32 {
+    let spring = unsafe { &*(spring_ptr as *mut Spring) };
+    spring.velocity(time)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_spring_reset(spring_ptr: *mut c_void) {
+    let spring = unsafe { &mut *(spring_ptr as *mut Spring) };
+    spring.reset();
+}
+
 #[no_mangle]
 pub extern "C" fn fl_calculate_rubber_band_offset(offset: f32, range: f32) -> f32 {
     rubber_band::calculate_offset(offset, range)
@@ -139,9 +263,94 @@ pub extern "C" fn fl_calculate_rubber_band_offset_inv(offset: f32, range: f32) -
     rubber_band::calculate_offset_inv(offset, range)
 }
 
+#[no_mangle]
+pub extern "C" fn fl_calculate_rubber_band_derivative(offset: f32, range: f32) -> f32 {
+    rubber_band::calculate_derivative(offset, range)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_calculate_rubber_band_derivative_inv(offset: f32, range: f32) -> f32 {
+    rubber_band::calculate_derivative_inv(offset, range)
+}
+
+/// The built-in curves of a rubber band.
+///
+/// The curve is passed from C as a plain integer, because an enum argument with a value
+/// that is not one of its variants is undefined behavior in Rust.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlRubberBandCurve {
+    Reciprocal = 0,
+    Logarithmic = 1,
+    Exponential = 2,
+}
+
+impl FlRubberBandCurve {
+    /// Returns the curve with the given raw value, or the default reciprocal curve
+    /// if the value is unknown.
+    fn from_raw(value: c_int) -> Self {
+        match value {
+            1 => Self::Logarithmic,
+            2 => Self::Exponential,
+            _ => Self::Reciprocal,
+        }
+    }
+}
+
+fn rubber_band_with_curve(
+    coefficient: f32,
+    curve: c_int,
+) -> RubberBand<f32, Box<dyn RubberBandCurve<f32>>> {
+    match FlRubberBandCurve::from_raw(curve) {
+        FlRubberBandCurve::Reciprocal => RubberBand::new(coefficient, Reciprocal).boxed(),
+        FlRubberBandCurve::Logarithmic => RubberBand::new(coefficient, Logarithmic).boxed(),
+        FlRubberBandCurve::Exponential => RubberBand::new(coefficient, Exponential).boxed(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn fl_calculate_rubber_band_offset_with_curve(
+    offset: f32,
+    range: f32,
+    coefficient: f32,
+    curve: c_int,
+) -> f32 {
+    rubber_band_with_curve(coefficient, curve).calculate_offset(offset, range)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_calculate_rubber_band_offset_inv_with_curve(
+    offset: f32,
+    range: f32,
+    coefficient: f32,
+    curve: c_int,
+) -> f32 {
+    rubber_band_with_curve(coefficient, curve).calculate_offset_inv(offset, range)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_calculate_rubber_band_derivative_with_curve(
+    offset: f32,
+    range: f32,
+    coefficient: f32,
+    curve: c_int,
+) -> f32 {
+    rubber_band_with_curve(coefficient, curve).calculate_derivative(offset, range)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_calculate_rubber_band_derivative_inv_with_curve(
+    offset: f32,
+    range: f32,
+    coefficient: f32,
+    curve: c_int,
+) -> f32 {
+    rubber_band_with_curve(coefficient, curve).calculate_derivative_inv(offset, range)
+}
+
 #[no_mangle]
 pub extern "C" fn fl_velocity_tracker_new(strategy: Strategy) -> *mut c_void {
-    let velocity_tracker = Box::new(VelocityTracker::with_strategy(strategy));
+    let velocity_tracker = Box::new(VelocityTracker::<f32>::with_strategy(strategy));
     Box::into_raw(velocity_tracker) as *mut _
 }
 
@@ -150,6 +359,32 @@ pub extern "C" fn fl_velocity_tracker_new_default() -> *mut c_void {
     fl_velocity_tracker_new(Strategy::Recurrence)
 }
 
+fn velocity_tracker_config(
+    strategy: Strategy,
+    history_size: usize,
+    horizon: f32,
+    pointer_stopped_threshold: f32,
+) -> VelocityTrackerConfig {
+    VelocityTrackerConfig::new()
+        .with_strategy(strategy)
+        .with_history_size(history_size)
+        .with_horizon(horizon)
+        .with_pointer_stopped_threshold(pointer_stopped_threshold)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_new_with_config(
+    strategy: Strategy,
+    history_size: usize,
+    horizon: f32,
+    pointer_stopped_threshold: f32,
+) -> *mut c_void {
+    let config =
+        velocity_tracker_config(strategy, history_size, horizon, pointer_stopped_threshold);
+    let velocity_tracker = Box::new(VelocityTracker::<f32>::with_config(config));
+    Box::into_raw(velocity_tracker) as *mut _
+}
+
 #[no_mangle]
 pub extern "C" fn fl_velocity_tracker_free(velocity_tracker_ptr: *mut c_void) {
     let velocity_tracker = unsafe { Box::from_raw(velocity_tracker_ptr as *mut VelocityTracker) };
@@ -172,13 +407,810 @@ pub extern "C" fn fl_velocity_tracker_calculate_velocity(velocity_tracker_ptr: *
     velocity_tracker.calculate()
 }
 
+/// Computes the velocity at the given time, which is zero if the pointer has rested
+/// before it.
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_calculate_velocity_at(
+    velocity_tracker_ptr: *mut c_void,
+    time: f32,
+) -> f32 {
+    let velocity_tracker = unsafe { &*(velocity_tracker_ptr as *mut VelocityTracker) };
+    velocity_tracker.calculate_at(time)
+}
+
 #[no_mangle]
 pub extern "C" fn fl_velocity_tracker_reset(velocity_tracker_ptr: *mut c_void) {
     let velocity_tracker = unsafe { &mut *(velocity_tracker_ptr as *mut VelocityTracker) };
     velocity_tracker.reset();
 }
 
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_2d_new(strategy: Strategy) -> *mut c_void {
+    let velocity_tracker = Box::new(VelocityTracker2D::with_strategy(strategy));
+    Box::into_raw(velocity_tracker) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_2d_new_with_config(
+    strategy: Strategy,
+    history_size: usize,
+    horizon: f32,
+    pointer_stopped_threshold: f32,
+) -> *mut c_void {
+    let config =
+        velocity_tracker_config(strategy, history_size, horizon, pointer_stopped_threshold);
+    let velocity_tracker = Box::new(VelocityTracker2D::with_config(config));
+    Box::into_raw(velocity_tracker) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_2d_free(velocity_tracker_ptr: *mut c_void) {
+    let velocity_tracker = unsafe { Box::from_raw(velocity_tracker_ptr as *mut VelocityTracker2D) };
+    drop(velocity_tracker)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_2d_add_data_point(
+    velocity_tracker_ptr: *mut c_void,
+    time: f32,
+    position: Point,
+) {
+    let velocity_tracker = unsafe { &mut *(velocity_tracker_ptr as *mut VelocityTracker2D) };
+    velocity_tracker.add_data_point(time, position);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_2d_calculate_velocity(
+    velocity_tracker_ptr: *mut c_void,
+) -> Point {
+    let velocity_tracker = unsafe { &*(velocity_tracker_ptr as *mut VelocityTracker2D) };
+    velocity_tracker.calculate()
+}
+
+/// Computes the velocity vector at the given time, which is zero if the pointer has rested
+/// before it.
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_2d_calculate_velocity_at(
+    velocity_tracker_ptr: *mut c_void,
+    time: f32,
+) -> Point {
+    let velocity_tracker = unsafe { &*(velocity_tracker_ptr as *mut VelocityTracker2D) };
+    velocity_tracker.calculate_at(time)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_velocity_tracker_2d_reset(velocity_tracker_ptr: *mut c_void) {
+    let velocity_tracker = unsafe { &mut *(velocity_tracker_ptr as *mut VelocityTracker2D) };
+    velocity_tracker.reset();
+}
+
 #[no_mangle]
 pub extern "C" fn fl_velocity_approaching_halt(horizontal: f32, vertical: f32) -> bool {
     VelocityTracker::approaching_halt(horizontal, vertical)
 }
+
+#[no_mangle]
+pub extern "C" fn fl_directional_lock_new() -> *mut c_void {
+    let directional_lock = Box::new(DirectionalLock::new());
+    Box::into_raw(directional_lock) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_directional_lock_free(directional_lock_ptr: *mut c_void) {
+    let directional_lock = unsafe { Box::from_raw(directional_lock_ptr as *mut DirectionalLock) };
+    drop(directional_lock)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_directional_lock_set_angle_tolerance(
+    directional_lock_ptr: *mut c_void,
+    angle_tolerance: f32,
+) {
+    let directional_lock = unsafe { &mut *(directional_lock_ptr as *mut DirectionalLock) };
+    directional_lock.set_angle_tolerance(angle_tolerance);
+}
+
+/// Updates the lock with the translation and the velocity of the gesture, and returns `true`
+/// once the direction is decided.
+#[no_mangle]
+pub extern "C" fn fl_directional_lock_update(
+    directional_lock_ptr: *mut c_void,
+    translation: Point,
+    velocity: Point,
+) -> bool {
+    let directional_lock = unsafe { &mut *(directional_lock_ptr as *mut DirectionalLock) };
+    directional_lock.update(translation, velocity).is_some()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_directional_lock_filter_translation(
+    directional_lock_ptr: *mut c_void,
+    translation: Point,
+) -> Point {
+    let directional_lock = unsafe { &*(directional_lock_ptr as *mut DirectionalLock) };
+    directional_lock.filter_translation(translation)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_directional_lock_filter(
+    directional_lock_ptr: *mut c_void,
+    point: Point,
+) -> Point {
+    let directional_lock = unsafe { &*(directional_lock_ptr as *mut DirectionalLock) };
+    directional_lock.filter(point)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_directional_lock_reset(directional_lock_ptr: *mut c_void) {
+    let directional_lock = unsafe { &mut *(directional_lock_ptr as *mut DirectionalLock) };
+    directional_lock.reset();
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_new() -> *mut c_void {
+    let scroll_controller = Box::new(ScrollController::new());
+    Box::into_raw(scroll_controller) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_free(scroll_controller_ptr: *mut c_void) {
+    let scroll_controller =
+        unsafe { Box::from_raw(scroll_controller_ptr as *mut ScrollController) };
+    drop(scroll_controller)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_content_length(
+    scroll_controller_ptr: *mut c_void,
+    content_length: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_content_length(content_length);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_viewport_length(
+    scroll_controller_ptr: *mut c_void,
+    viewport_length: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_viewport_length(viewport_length);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_content_inset(
+    scroll_controller_ptr: *mut c_void,
+    content_inset: AxisInsets,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_content_inset(content_inset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_safe_area_inset(
+    scroll_controller_ptr: *mut c_void,
+    safe_area_inset: AxisInsets,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_safe_area_inset(safe_area_inset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_additional_inset(
+    scroll_controller_ptr: *mut c_void,
+    additional_inset: AxisInsets,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_additional_inset(additional_inset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_adjusted_content_inset(
+    scroll_controller_ptr: *mut c_void,
+) -> AxisInsets {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.adjusted_content_inset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_deceleration_rate(
+    scroll_controller_ptr: *mut c_void,
+    deceleration_rate: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_deceleration_rate(DecelerationRate(deceleration_rate));
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_bounce_response(
+    scroll_controller_ptr: *mut c_void,
+    bounce_response: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_bounce_response(bounce_response);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_rubber_band(
+    scroll_controller_ptr: *mut c_void,
+    coefficient: f32,
+    curve: c_int,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_rubber_band(rubber_band_with_curve(coefficient, curve));
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_min_offset(scroll_controller_ptr: *mut c_void) -> f32 {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.min_offset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_max_offset(scroll_controller_ptr: *mut c_void) -> f32 {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.max_offset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_offset(scroll_controller_ptr: *mut c_void) -> f32 {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.offset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_offset(scroll_controller_ptr: *mut c_void, offset: f32) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_offset(offset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_adjust_for_content_inserted(
+    scroll_controller_ptr: *mut c_void,
+    at: f32,
+    delta: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.adjust_for_content_inserted(at, delta);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_scale_content(
+    scroll_controller_ptr: *mut c_void,
+    ratio: f32,
+    focal: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.scale_content(ratio, focal);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_animate_to(
+    scroll_controller_ptr: *mut c_void,
+    now: f32,
+    offset: f32,
+    completion: FlScrollCompletion,
+    context: *mut c_void,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.animate_to_with_completion(
+        now,
+        offset,
+        scroll_completion(completion, context),
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_velocity(scroll_controller_ptr: *mut c_void) -> f32 {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.velocity()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_phase(scroll_controller_ptr: *mut c_void) -> ScrollPhase {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.phase()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_scroll_enabled(
+    scroll_controller_ptr: *mut c_void,
+    scroll_enabled: bool,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_scroll_enabled(scroll_enabled);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_bounces(
+    scroll_controller_ptr: *mut c_void,
+    bounces: bool,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_bounces(bounces);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_always_bounce(
+    scroll_controller_ptr: *mut c_void,
+    always_bounce: bool,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.set_always_bounce(always_bounce);
+}
+
+/// Enables paging with the given page size, or disables it if the page size is not positive.
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_set_page_size(
+    scroll_controller_ptr: *mut c_void,
+    page_size: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    let paging = if page_size > 0.0 {
+        Some(Paging::new(page_size))
+    } else {
+        None
+    };
+    scroll_controller.set_paging(paging);
+}
+
+/// Sets the offsets that the content is allowed to come to rest on,
+/// or removes them if `count` is zero.
+///
+/// # Safety
+///
+/// `offsets` must be null or point to `count` consecutive `f32` values.
+#[no_mangle]
+pub unsafe extern "C" fn fl_scroll_controller_set_snap_points(
+    scroll_controller_ptr: *mut c_void,
+    offsets: *const f32,
+    count: usize,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    let snap_points = if offsets.is_null() || count == 0 {
+        None
+    } else {
+        let offsets = unsafe { std::slice::from_raw_parts(offsets, count) };
+        Some(SnapPoints::new(offsets.to_vec()))
+    };
+    scroll_controller.set_snap_points(snap_points);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_can_scroll(scroll_controller_ptr: *mut c_void) -> bool {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.can_scroll()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_touch_began(
+    scroll_controller_ptr: *mut c_void,
+    time: f32,
+    position: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.touch_began(time, position);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_touch_moved(
+    scroll_controller_ptr: *mut c_void,
+    time: f32,
+    position: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.touch_moved(time, position);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_touch_ended(scroll_controller_ptr: *mut c_void, time: f32) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.touch_ended(time);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_tick(scroll_controller_ptr: *mut c_void, now: f32) -> f32 {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.tick(now)
+}
+
+/// The kinds of `ScrollEvent`, which are passed to a C observer.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FlScrollEventType {
+    WillBeginDragging = 0,
+    DidScroll = 1,
+    WillEndDragging = 2,
+    DidEndDecelerating = 3,
+    DidEndScrollingAnimation = 4,
+    DidBounce = 5,
+}
+
+/// A C callback that receives the scroll events of a controller.
+///
+/// The value is the offset of `DidScroll`, or the velocity of `WillEndDragging` and `DidBounce`.
+/// The target points to the adjustable target of `WillEndDragging`, and is null otherwise.
+///
+/// The callback runs while the controller is being mutated, so it must not call any
+/// `fl_scroll_controller_*` function with the same controller. Doing so is undefined behavior.
+type FlScrollObserver = Option<
+    extern "C" fn(context: *mut c_void, event: FlScrollEventType, value: f32, target: *mut f32),
+>;
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_add_observer(
+    scroll_controller_ptr: *mut c_void,
+    observer: FlScrollObserver,
+    context: *mut c_void,
+) -> u64 {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    let id = scroll_controller.add_observer(move |event| {
+        let Some(observer) = observer else {
+            return;
+        };
+        match event {
+            ScrollEvent::WillBeginDragging => observer(
+                context,
+                FlScrollEventType::WillBeginDragging,
+                0.0,
+                std::ptr::null_mut(),
+            ),
+            ScrollEvent::DidScroll { offset } => observer(
+                context,
+                FlScrollEventType::DidScroll,
+                *offset,
+                std::ptr::null_mut(),
+            ),
+            ScrollEvent::WillEndDragging { velocity, target } => observer(
+                context,
+                FlScrollEventType::WillEndDragging,
+                *velocity,
+                target,
+            ),
+            ScrollEvent::DidEndDecelerating => observer(
+                context,
+                FlScrollEventType::DidEndDecelerating,
+                0.0,
+                std::ptr::null_mut(),
+            ),
+            ScrollEvent::DidEndScrollingAnimation => observer(
+                context,
+                FlScrollEventType::DidEndScrollingAnimation,
+                0.0,
+                std::ptr::null_mut(),
+            ),
+            ScrollEvent::DidBounce { velocity } => observer(
+                context,
+                FlScrollEventType::DidBounce,
+                *velocity,
+                std::ptr::null_mut(),
+            ),
+        }
+    });
+    id.0
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_remove_observer(
+    scroll_controller_ptr: *mut c_void,
+    observer_id: u64,
+) -> bool {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController) };
+    scroll_controller.remove_observer(ObserverId(observer_id))
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_new() -> *mut c_void {
+    let scroll_controller = Box::new(ScrollController2D::new());
+    Box::into_raw(scroll_controller) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_free(scroll_controller_ptr: *mut c_void) {
+    let scroll_controller =
+        unsafe { Box::from_raw(scroll_controller_ptr as *mut ScrollController2D) };
+    drop(scroll_controller)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_horizontal(
+    scroll_controller_ptr: *mut c_void,
+) -> *mut c_void {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.horizontal_mut() as *mut ScrollController as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_vertical(
+    scroll_controller_ptr: *mut c_void,
+) -> *mut c_void {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.vertical_mut() as *mut ScrollController as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_set_content_size(
+    scroll_controller_ptr: *mut c_void,
+    content_size: Size,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.set_content_size(content_size);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_set_viewport_size(
+    scroll_controller_ptr: *mut c_void,
+    viewport_size: Size,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.set_viewport_size(viewport_size);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_set_content_inset(
+    scroll_controller_ptr: *mut c_void,
+    content_inset: EdgeInsets,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.set_content_inset(content_inset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_set_safe_area_inset(
+    scroll_controller_ptr: *mut c_void,
+    safe_area_inset: EdgeInsets,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.set_safe_area_inset(safe_area_inset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_set_additional_inset(
+    scroll_controller_ptr: *mut c_void,
+    additional_inset: EdgeInsets,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.set_additional_inset(additional_inset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_adjusted_content_inset(
+    scroll_controller_ptr: *mut c_void,
+) -> EdgeInsets {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.adjusted_content_inset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_set_directional_lock_enabled(
+    scroll_controller_ptr: *mut c_void,
+    enabled: bool,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    let directional_lock = if enabled {
+        Some(DirectionalLock::new())
+    } else {
+        None
+    };
+    scroll_controller.set_directional_lock(directional_lock);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_min_offset(scroll_controller_ptr: *mut c_void) -> Point {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.min_offset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_max_offset(scroll_controller_ptr: *mut c_void) -> Point {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.max_offset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_offset(scroll_controller_ptr: *mut c_void) -> Point {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.offset()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_set_offset(
+    scroll_controller_ptr: *mut c_void,
+    offset: Point,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.set_offset(offset);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_scale_content(
+    scroll_controller_ptr: *mut c_void,
+    ratio: f32,
+    focal_point: Point,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.scale_content(ratio, focal_point);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_animate_to(
+    scroll_controller_ptr: *mut c_void,
+    now: f32,
+    offset: Point,
+    completion: FlScrollCompletion,
+    context: *mut c_void,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.animate_to_with_completion(
+        now,
+        offset,
+        scroll_completion(completion, context),
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_velocity(scroll_controller_ptr: *mut c_void) -> Point {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.velocity()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_phase(scroll_controller_ptr: *mut c_void) -> ScrollPhase {
+    let scroll_controller = unsafe { &*(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.phase()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_touch_began(
+    scroll_controller_ptr: *mut c_void,
+    time: f32,
+    position: Point,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.touch_began(time, position);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_touch_moved(
+    scroll_controller_ptr: *mut c_void,
+    time: f32,
+    position: Point,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.touch_moved(time, position);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_touch_ended(
+    scroll_controller_ptr: *mut c_void,
+    time: f32,
+) {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.touch_ended(time);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_scroll_controller_2d_tick(
+    scroll_controller_ptr: *mut c_void,
+    now: f32,
+) -> Point {
+    let scroll_controller = unsafe { &mut *(scroll_controller_ptr as *mut ScrollController2D) };
+    scroll_controller.tick(now)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_new() -> *mut c_void {
+    let zoom_controller = Box::new(ZoomController::new());
+    Box::into_raw(zoom_controller) as *mut _
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_free(zoom_controller_ptr: *mut c_void) {
+    let zoom_controller = unsafe { Box::from_raw(zoom_controller_ptr as *mut ZoomController) };
+    drop(zoom_controller)
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_set_min_scale(
+    zoom_controller_ptr: *mut c_void,
+    min_scale: f32,
+) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.set_min_scale(min_scale);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_set_max_scale(
+    zoom_controller_ptr: *mut c_void,
+    max_scale: f32,
+) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.set_max_scale(max_scale);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_set_bounces_zoom(
+    zoom_controller_ptr: *mut c_void,
+    bounces_zoom: bool,
+) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.set_bounces_zoom(bounces_zoom);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_scale(zoom_controller_ptr: *mut c_void) -> f32 {
+    let zoom_controller = unsafe { &*(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.scale()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_set_scale(zoom_controller_ptr: *mut c_void, scale: f32) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.set_scale(scale);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_focal_point(zoom_controller_ptr: *mut c_void) -> Point {
+    let zoom_controller = unsafe { &*(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.focal_point()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_phase(zoom_controller_ptr: *mut c_void) -> ZoomPhase {
+    let zoom_controller = unsafe { &*(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.phase()
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_pinch_began(
+    zoom_controller_ptr: *mut c_void,
+    time: f32,
+    focal_point: Point,
+) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.pinch_began(time, focal_point);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_pinch_changed(
+    zoom_controller_ptr: *mut c_void,
+    time: f32,
+    pinch_scale: f32,
+    focal_point: Point,
+) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.pinch_changed(time, pinch_scale, focal_point);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_pinch_ended(zoom_controller_ptr: *mut c_void, time: f32) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.pinch_ended(time);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_animate_to(
+    zoom_controller_ptr: *mut c_void,
+    now: f32,
+    scale: f32,
+    focal_point: Point,
+) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.animate_to(now, scale, focal_point);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_double_tap(
+    zoom_controller_ptr: *mut c_void,
+    now: f32,
+    focal_point: Point,
+) {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.double_tap(now, focal_point);
+}
+
+#[no_mangle]
+pub extern "C" fn fl_zoom_controller_tick(zoom_controller_ptr: *mut c_void, now: f32) -> f32 {
+    let zoom_controller = unsafe { &mut *(zoom_controller_ptr as *mut ZoomController) };
+    zoom_controller.tick(now)
+}
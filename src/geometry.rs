@@ -0,0 +1,140 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::ops::Add;
+
+/// A point or a vector in a two-dimensional coordinate system.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const ZERO: Self = Self { x: 0.0, y: 0.0 };
+
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// A structure that contains width and height values.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Size {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Size {
+    pub const ZERO: Self = Self {
+        width: 0.0,
+        height: 0.0,
+    };
+
+    pub fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+}
+
+/// The distance that the content is inset from the edges of the viewport along one axis.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AxisInsets {
+    /// The inset at the start of the axis (top or left).
+    pub leading: f32,
+    /// The inset at the end of the axis (bottom or right).
+    pub trailing: f32,
+}
+
+impl AxisInsets {
+    pub const ZERO: Self = Self {
+        leading: 0.0,
+        trailing: 0.0,
+    };
+
+    pub fn new(leading: f32, trailing: f32) -> Self {
+        Self { leading, trailing }
+    }
+}
+
+impl Add for AxisInsets {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.leading + rhs.leading, self.trailing + rhs.trailing)
+    }
+}
+
+/// The inset distances for the four edges of the viewport.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct EdgeInsets {
+    pub top: f32,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+}
+
+impl EdgeInsets {
+    pub const ZERO: Self = Self {
+        top: 0.0,
+        left: 0.0,
+        bottom: 0.0,
+        right: 0.0,
+    };
+
+    pub fn new(top: f32, left: f32, bottom: f32, right: f32) -> Self {
+        Self {
+            top,
+            left,
+            bottom,
+            right,
+        }
+    }
+
+    /// Creates the insets from the insets along each axis.
+    pub fn from_axes(horizontal: AxisInsets, vertical: AxisInsets) -> Self {
+        Self::new(
+            vertical.leading,
+            horizontal.leading,
+            vertical.trailing,
+            horizontal.trailing,
+        )
+    }
+
+    /// The insets along the horizontal axis.
+    pub fn horizontal(&self) -> AxisInsets {
+        AxisInsets::new(self.left, self.right)
+    }
+
+    /// The insets along the vertical axis.
+    pub fn vertical(&self) -> AxisInsets {
+        AxisInsets::new(self.top, self.bottom)
+    }
+}
+
+impl Add for EdgeInsets {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.top + rhs.top,
+            self.left + rhs.left,
+            self.bottom + rhs.bottom,
+            self.right + rhs.right,
+        )
+    }
+}
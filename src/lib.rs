@@ -12,15 +12,44 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("Either the `std` or the `libm` feature must be enabled.");
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 mod constants;
+pub mod directional_lock;
+pub mod geometry;
+mod math;
+pub mod paging;
 pub mod rubber_band;
+#[cfg(feature = "alloc")]
+pub mod scroll_controller;
+#[cfg(feature = "alloc")]
+pub mod scroll_controller_2d;
 pub mod scroller;
+#[cfg(feature = "alloc")]
+pub mod snapping;
+mod spring;
 mod spring_back;
 mod velocity_tracker;
+pub mod zoom;
 
+pub use math::Float;
+#[cfg(feature = "alloc")]
+pub use scroll_controller::{ObserverId, ScrollController, ScrollEvent, ScrollPhase};
+#[cfg(feature = "alloc")]
+pub use scroll_controller_2d::ScrollController2D;
 pub use scroller::Scroller;
-pub use spring_back::SpringBack;
-pub use velocity_tracker::{Strategy as VelocityTrackerStrategy, VelocityTracker};
+pub use spring::Spring;
+pub use spring_back::{SpringBack, SpringBackValue};
+pub use velocity_tracker::{
+    Strategy as VelocityTrackerStrategy, VelocityTracker, VelocityTracker2D, VelocityTrackerConfig,
+};
+pub use zoom::{ZoomController, ZoomPhase};
 
 #[cfg(feature = "ffi")]
 pub mod ffi;
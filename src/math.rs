@@ -0,0 +1,100 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::{
+    fmt::Debug,
+    iter::Sum,
+    ops::{AddAssign, DivAssign, MulAssign, SubAssign},
+};
+
+/// A floating point type that the simulations can be calculated with.
+///
+/// It is implemented for `f32` and `f64`. Without `std`, the math functions are provided
+/// by `libm`.
+pub trait Float:
+    num_traits::Float
+    + num_traits::FloatConst
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Default
+    + Debug
+    + Send
+    + Sync
+    + 'static
+{
+    /// Converts a constant to this type, rounding it if needed.
+    fn from_f64(value: f64) -> Self;
+}
+
+impl Float for f32 {
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl Float for f64 {
+    #[inline]
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+}
+
+// The math functions are called through these shims, so that callers with concrete types
+// don't need to import `num_traits::Float` only when `std` is disabled.
+
+/// Returns the four quadrant arctangent of `y` and `x` in radians.
+#[inline]
+pub(crate) fn atan2<T: Float>(y: T, x: T) -> T {
+    y.atan2(x)
+}
+
+/// Returns the length of the hypotenuse of a right triangle with legs `x` and `y`.
+#[inline]
+pub(crate) fn hypot<T: Float>(x: T, y: T) -> T {
+    x.hypot(y)
+}
+
+/// Returns the natural logarithm of `value`.
+#[inline]
+pub(crate) fn ln<T: Float>(value: T) -> T {
+    value.ln()
+}
+
+/// Returns `e` raised to the power of `value`.
+#[inline]
+pub(crate) fn exp<T: Float>(value: T) -> T {
+    value.exp()
+}
+
+/// Returns the largest integer less than or equal to `value`.
+#[inline]
+pub(crate) fn floor<T: Float>(value: T) -> T {
+    value.floor()
+}
+
+/// Returns the smallest integer greater than or equal to `value`.
+#[inline]
+pub(crate) fn ceil<T: Float>(value: T) -> T {
+    value.ceil()
+}
+
+/// Returns the nearest integer to `value`, rounding half-way cases away from zero.
+#[inline]
+pub(crate) fn round<T: Float>(value: T) -> T {
+    value.round()
+}
@@ -0,0 +1,110 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    math::{ceil, floor, round},
+    spring_back::{valid_response, DEFAULT_RESPONSE},
+};
+
+/// The default release velocity, in points per millisecond, above which the content advances
+/// to the adjacent page instead of settling on the nearest one.
+pub const DEFAULT_PAGING_VELOCITY_THRESHOLD: f32 = 0.5_f32;
+
+/// A behavior that makes the content come to rest on multiples of the page size.
+///
+/// Pages are laid out from offset 0 with a fixed size. When the touch is released, the
+/// destination page is chosen from the current offset and the release velocity, and the
+/// content is animated to it with a `SpringBack`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Paging {
+    page_size: f32,
+    velocity_threshold: f32,
+    response: f32,
+}
+
+impl Paging {
+    pub fn new(page_size: f32) -> Self {
+        Self {
+            page_size,
+            velocity_threshold: DEFAULT_PAGING_VELOCITY_THRESHOLD,
+            response: DEFAULT_RESPONSE,
+        }
+    }
+
+    pub fn page_size(&self) -> f32 {
+        self.page_size
+    }
+
+    pub fn set_page_size(&mut self, page_size: f32) {
+        self.page_size = page_size;
+    }
+
+    /// The release velocity in points per millisecond above which the content advances
+    /// to the adjacent page in the direction of the velocity.
+    pub fn velocity_threshold(&self) -> f32 {
+        self.velocity_threshold
+    }
+
+    pub fn set_velocity_threshold(&mut self, velocity_threshold: f32) {
+        self.velocity_threshold = velocity_threshold.abs();
+    }
+
+    /// The response of the spring that moves the content to the destination page.
+    pub fn response(&self) -> f32 {
+        self.response
+    }
+
+    pub fn set_response(&mut self, response: f32) {
+        self.response = valid_response(response);
+    }
+
+    /// Returns the index of the page that the content should come to rest on,
+    /// given the offset and the velocity at the moment the touch is released.
+    pub fn target_page(&self, offset: f32, velocity: f32) -> i32 {
+        if self.page_size <= 0.0 {
+            return 0;
+        }
+        let position = offset / self.page_size;
+        let page = if velocity > self.velocity_threshold {
+            floor(position) + 1.0
+        } else if velocity < -self.velocity_threshold {
+            ceil(position) - 1.0
+        } else {
+            round(position)
+        };
+        page as i32
+    }
+
+    /// Returns the offset of the page that the content should come to rest on.
+    pub fn target_offset(&self, offset: f32, velocity: f32) -> f32 {
+        self.target_page(offset, velocity) as f32 * self.page_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Paging;
+
+    #[test]
+    fn test_target_page() {
+        let paging = Paging::new(400.0);
+        // Slow releases settle on the nearest page.
+        assert_eq!(paging.target_page(590.0, 0.1), 1);
+        assert_eq!(paging.target_page(610.0, -0.1), 2);
+        // Fast releases advance to the adjacent page in the direction of the velocity.
+        assert_eq!(paging.target_page(420.0, 1.0), 2);
+        assert_eq!(paging.target_page(780.0, -1.0), 1);
+        assert_eq!(paging.target_page(800.0, 1.0), 3);
+    }
+}
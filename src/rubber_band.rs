@@ -12,31 +12,265 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-const RUBBER_BAND_COEFFICIENT: f32 = 0.55_f32;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::fmt::Debug;
 
-pub fn calculate_offset(offset: f32, range: f32) -> f32 {
-    // Check if offset and range are positive.
-    if offset < 0_f32 || range <= 0_f32 {
-        return 0_f32;
+use crate::math::Float;
+
+/// The default coefficient, which matches the resistance of `UIScrollView`.
+pub const DEFAULT_COEFFICIENT: f64 = 0.55;
+
+/// A curve that maps the distance that the content is pulled beyond its edge to the
+/// distance that it is displayed at.
+///
+/// Both distances are normalized by the range of the rubber band, which is usually the
+/// viewport length. The curve must be increasing, start at 0, and be invertible, so that
+/// a displayed offset can be converted back when a new drag begins.
+pub trait RubberBandCurve<T: Float>: Debug {
+    /// Returns the displayed distance of a non-negative pulled distance.
+    fn apply(&self, distance: T, coefficient: T) -> T;
+
+    /// Returns the pulled distance that is displayed at the given non-negative distance.
+    fn invert(&self, displayed: T, coefficient: T) -> T;
+
+    /// Returns the derivative of `apply` at the given pulled distance, which is the ratio of
+    /// the displayed velocity to the velocity that the content is pulled with.
+    fn derivative(&self, distance: T, coefficient: T) -> T;
+
+    /// Returns the derivative of `invert` at the given displayed distance.
+    ///
+    /// The default implementation takes the reciprocal of `derivative` at the inverted distance.
+    fn inverse_derivative(&self, displayed: T, coefficient: T) -> T {
+        T::one() / self.derivative(self.invert(displayed, coefficient), coefficient)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Float, C: RubberBandCurve<T> + ?Sized> RubberBandCurve<T> for Box<C> {
+    fn apply(&self, distance: T, coefficient: T) -> T {
+        (**self).apply(distance, coefficient)
+    }
+
+    fn invert(&self, displayed: T, coefficient: T) -> T {
+        (**self).invert(displayed, coefficient)
+    }
+
+    fn derivative(&self, distance: T, coefficient: T) -> T {
+        (**self).derivative(distance, coefficient)
+    }
+
+    fn inverse_derivative(&self, displayed: T, coefficient: T) -> T {
+        (**self).inverse_derivative(displayed, coefficient)
+    }
+}
+
+/// The curve of `UIScrollView`, which approaches the range as the content is pulled further.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reciprocal;
+
+impl<T: Float> RubberBandCurve<T> for Reciprocal {
+    fn apply(&self, distance: T, coefficient: T) -> T {
+        let one = T::one();
+        one - one / (distance * coefficient + one)
+    }
+
+    fn invert(&self, displayed: T, coefficient: T) -> T {
+        // The displayed distance only approaches 1 infinitely, so it is clamped slightly below 1
+        // to keep the result finite.
+        let displayed = displayed.min(T::one() - T::epsilon() * T::from_f64(16.0));
+        displayed / ((T::one() - displayed) * coefficient)
+    }
+
+    fn derivative(&self, distance: T, coefficient: T) -> T {
+        let denominator = distance * coefficient + T::one();
+        coefficient / (denominator * denominator)
+    }
+
+    fn inverse_derivative(&self, displayed: T, coefficient: T) -> T {
+        let displayed = displayed.min(T::one() - T::epsilon() * T::from_f64(16.0));
+        let remaining = T::one() - displayed;
+        T::one() / (coefficient * remaining * remaining)
+    }
+}
+
+/// A curve that keeps growing slowly without a limit, so the content can always be pulled
+/// further.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Logarithmic;
+
+impl<T: Float> RubberBandCurve<T> for Logarithmic {
+    fn apply(&self, distance: T, coefficient: T) -> T {
+        (distance * coefficient).ln_1p()
+    }
+
+    fn invert(&self, displayed: T, coefficient: T) -> T {
+        displayed.exp_m1() / coefficient
+    }
+
+    fn derivative(&self, distance: T, coefficient: T) -> T {
+        coefficient / (distance * coefficient + T::one())
+    }
+
+    fn inverse_derivative(&self, displayed: T, coefficient: T) -> T {
+        displayed.exp() / coefficient
+    }
+}
+
+/// A curve that saturates quickly toward the range, like the stretch overscroll effect
+/// of Android 12.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Exponential;
+
+impl<T: Float> RubberBandCurve<T> for Exponential {
+    fn apply(&self, distance: T, coefficient: T) -> T {
+        -(-distance * coefficient).exp_m1()
+    }
+
+    fn invert(&self, displayed: T, coefficient: T) -> T {
+        // Like the reciprocal curve, the displayed distance only approaches 1 infinitely.
+        let displayed = displayed.min(T::one() - T::epsilon() * T::from_f64(16.0));
+        -(-displayed).ln_1p() / coefficient
+    }
+
+    fn derivative(&self, distance: T, coefficient: T) -> T {
+        coefficient * (-distance * coefficient).exp()
+    }
+
+    fn inverse_derivative(&self, displayed: T, coefficient: T) -> T {
+        let displayed = displayed.min(T::one() - T::epsilon() * T::from_f64(16.0));
+        T::one() / (coefficient * (T::one() - displayed))
+    }
+}
+
+/// Maps an offset beyond the edge of the content like a rubber band.
+///
+/// The resistance is tuned by the coefficient, where a greater value makes the content
+/// follow the touch more closely. The shape of the resistance is defined by the curve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RubberBand<T = f32, C = Reciprocal> {
+    coefficient: T,
+    curve: C,
+}
+
+impl<T: Float, C: RubberBandCurve<T>> RubberBand<T, C> {
+    pub fn new(coefficient: T, curve: C) -> Self {
+        Self {
+            coefficient: coefficient.max(T::zero()),
+            curve,
+        }
+    }
+
+    pub fn coefficient(&self) -> T {
+        self.coefficient
+    }
+
+    pub fn set_coefficient(&mut self, coefficient: T) {
+        self.coefficient = coefficient.max(T::zero());
+    }
+
+    pub fn curve(&self) -> &C {
+        &self.curve
+    }
+
+    /// Returns the displayed offset of the content pulled by `offset` beyond its edge.
+    pub fn calculate_offset(&self, offset: T, range: T) -> T {
+        // Check if offset and range are positive.
+        if offset < T::zero() || range <= T::zero() || self.coefficient <= T::zero() {
+            return T::zero();
+        }
+        self.curve.apply(offset / range, self.coefficient) * range
+    }
+
+    /// Returns the offset that the content is pulled by to be displayed at `offset`.
+    ///
+    /// It is the exact inverse of `calculate_offset`.
+    pub fn calculate_offset_inv(&self, offset: T, range: T) -> T {
+        if offset < T::zero() || range <= T::zero() || self.coefficient <= T::zero() {
+            return T::zero();
+        }
+        self.curve.invert(offset / range, self.coefficient) * range
+    }
+
+    /// Returns the derivative of `calculate_offset` at the given pulled offset.
+    ///
+    /// Multiplying the velocity that the content is pulled with by the derivative gives the
+    /// velocity that it is displayed with. The mapping is applied symmetrically on both edges,
+    /// so the sign of the offset is ignored.
+    pub fn calculate_derivative(&self, offset: T, range: T) -> T {
+        if range <= T::zero() || self.coefficient <= T::zero() {
+            return T::zero();
+        }
+        self.curve
+            .derivative(offset.abs() / range, self.coefficient)
+    }
+
+    /// Returns the derivative of `calculate_offset_inv` at the given displayed offset.
+    ///
+    /// Multiplying the displayed velocity by the derivative gives the velocity that the content
+    /// is pulled with. The sign of the offset is ignored.
+    pub fn calculate_derivative_inv(&self, offset: T, range: T) -> T {
+        if range <= T::zero() || self.coefficient <= T::zero() {
+            return T::zero();
+        }
+        self.curve
+            .inverse_derivative(offset.abs() / range, self.coefficient)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Float, C: RubberBandCurve<T> + 'static> RubberBand<T, C> {
+    /// Erases the type of the curve, so rubber bands with different curves can be stored
+    /// in the same place.
+    pub fn boxed(self) -> RubberBand<T, Box<dyn RubberBandCurve<T>>> {
+        RubberBand {
+            coefficient: self.coefficient,
+            curve: Box::new(self.curve),
+        }
+    }
+}
+
+impl<T: Float> RubberBand<T> {
+    /// Creates the rubber band of `UIScrollView` with a custom coefficient.
+    pub fn with_coefficient(coefficient: T) -> Self {
+        Self::new(coefficient, Reciprocal)
     }
-    (1_f32 - (1_f32 / (offset / range * RUBBER_BAND_COEFFICIENT + 1_f32))) * range
 }
 
-pub fn calculate_offset_inv(mut offset: f32, range: f32) -> f32 {
-    if offset < 0_f32 || range < 0_f32 {
-        return 0_f32;
+impl<T: Float, C: RubberBandCurve<T> + Default> Default for RubberBand<T, C> {
+    fn default() -> Self {
+        Self::new(T::from_f64(DEFAULT_COEFFICIENT), C::default())
     }
-    // The offset and range cannot be equal.
-    // Offset only approaches range infinitely.
-    //
-    // To ensure valid calculation, we set the maximum value of offset slightly smaller than range.
-    offset = offset.min(range - 1e-5);
-    (range * offset / (range - offset)) / RUBBER_BAND_COEFFICIENT
+}
+
+/// Maps an offset with the default rubber band of `UIScrollView`.
+pub fn calculate_offset<T: Float>(offset: T, range: T) -> T {
+    RubberBand::<T>::default().calculate_offset(offset, range)
+}
+
+/// The inverse of `calculate_offset`.
+pub fn calculate_offset_inv<T: Float>(offset: T, range: T) -> T {
+    RubberBand::<T>::default().calculate_offset_inv(offset, range)
+}
+
+/// The derivative of `calculate_offset`, which converts a velocity from the pulled offset
+/// to the displayed offset.
+pub fn calculate_derivative<T: Float>(offset: T, range: T) -> T {
+    RubberBand::<T>::default().calculate_derivative(offset, range)
+}
+
+/// The derivative of `calculate_offset_inv`, which converts a velocity from the displayed
+/// offset to the pulled offset.
+pub fn calculate_derivative_inv<T: Float>(offset: T, range: T) -> T {
+    RubberBand::<T>::default().calculate_derivative_inv(offset, range)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{calculate_offset, calculate_offset_inv};
+    use super::{
+        calculate_offset, calculate_offset_inv, Exponential, Logarithmic, Reciprocal, RubberBand,
+        RubberBandCurve,
+    };
 
     #[test]
     fn it_works() {
@@ -46,4 +280,84 @@ mod tests {
         let inv = calculate_offset_inv(offset, range);
         assert!((origin - inv).abs() < 1e-2);
     }
+
+    #[test]
+    fn test_f64_large_range() {
+        let origin = 5_000_000_f64;
+        let range = 1_000_000_f64;
+        let offset = calculate_offset(origin, range);
+        let inv = calculate_offset_inv(offset, range);
+        assert!((origin - inv).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_curves_are_invertible() {
+        let range = 600_f32;
+        for coefficient in [0.3_f32, 0.55, 1.2] {
+            let reciprocal = RubberBand::new(coefficient, Reciprocal);
+            let logarithmic = RubberBand::new(coefficient, Logarithmic);
+            let exponential = RubberBand::new(coefficient, Exponential);
+            for origin in [0_f32, 1.0, 120.0, 600.0, 1500.0] {
+                let offsets = [
+                    reciprocal
+                        .calculate_offset_inv(reciprocal.calculate_offset(origin, range), range),
+                    logarithmic
+                        .calculate_offset_inv(logarithmic.calculate_offset(origin, range), range),
+                    exponential
+                        .calculate_offset_inv(exponential.calculate_offset(origin, range), range),
+                ];
+                for offset in offsets {
+                    assert!(
+                        (offset - origin).abs() < origin * 1e-3 + 1e-3,
+                        "{} {}",
+                        origin,
+                        offset
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_coefficient() {
+        let range = 600_f32;
+        assert_eq!(
+            RubberBand::<f32>::default().calculate_offset(200_f32, range),
+            calculate_offset(200_f32, range)
+        );
+        // A greater coefficient makes the content follow the touch more closely.
+        let loose = RubberBand::with_coefficient(0.8_f32);
+        assert!(loose.calculate_offset(200_f32, range) > calculate_offset(200_f32, range));
+        assert!(loose.calculate_offset(200_f32, range) < 200_f32);
+        // The default coefficient is not widened from `f32`.
+        assert_eq!(RubberBand::<f64>::default().coefficient(), 0.55);
+    }
+
+    #[test]
+    fn test_derivatives() {
+        fn check<C: RubberBandCurve<f64>>(curve: C) {
+            let range = 600_f64;
+            let rubber_band = RubberBand::new(0.55, curve);
+            for offset in [0_f64, 30.0, 300.0, 900.0] {
+                let step = 1e-3;
+                let numeric = (rubber_band.calculate_offset(offset + step, range)
+                    - rubber_band.calculate_offset(offset, range))
+                    / step;
+                let derivative = rubber_band.calculate_derivative(offset, range);
+                assert!((numeric - derivative).abs() < 1e-4, "{:?}", rubber_band);
+
+                // The derivative of the inverse is the reciprocal at the mapped offset.
+                let displayed = rubber_band.calculate_offset(offset, range);
+                let inverse = rubber_band.calculate_derivative_inv(displayed, range);
+                assert!(
+                    (inverse * derivative - 1.0).abs() < 1e-6,
+                    "{:?}",
+                    rubber_band
+                );
+            }
+        }
+        check(Reciprocal);
+        check(Logarithmic);
+        check(Exponential);
+    }
 }
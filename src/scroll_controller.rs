@@ -0,0 +1,1355 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+
+use crate::{
+    geometry::AxisInsets,
+    paging::Paging,
+    rubber_band::{RubberBand, RubberBandCurve},
+    scroller::{DecelerationRate, Scroller},
+    snapping::{SnapPoints, SnapTrajectory},
+    spring_back::{valid_response, SpringBack, DEFAULT_RESPONSE},
+    velocity_tracker::VelocityTracker,
+};
+
+/// The overflow distance is divided by this value to get the initial bounce velocity
+/// when the touch is released outside of the scrollable range.
+const OVERFLOW_VELOCITY_DIVISOR: f32 = 100_f32;
+
+/// The spring response of a programmatic scroll animation over a very short distance.
+///
+/// The response grows with the distance, and reaches `DEFAULT_RESPONSE` when the content moves
+/// by a whole viewport or more.
+const MIN_ANIMATION_RESPONSE: f32 = 0.3_f32;
+
+/// The simulation that drives the content after the touch is released.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+enum Animation {
+    #[default]
+    None,
+    /// The content decays with a `Scroller`.
+    Decay,
+    /// The content moves toward `spring_target` with a `SpringBack`.
+    Spring,
+}
+
+/// The phases that a scroll controller goes through during a scroll interaction.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ScrollPhase {
+    /// The content is at rest.
+    #[default]
+    Idle = 0,
+    /// The user has touched the content but has not moved yet.
+    Tracking = 1,
+    /// The user is dragging the content.
+    Dragging = 2,
+    /// The content keeps moving by inertia after the user lifted their finger.
+    Decelerating = 3,
+    /// The content springs back to the edge of the scrollable range.
+    Bouncing = 4,
+    /// The content moves to an offset requested by `animate_to`.
+    Animating = 5,
+}
+
+/// The events that a scroll controller sends to its observers during a scroll interaction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ScrollEvent {
+    /// The user is about to start dragging the content.
+    WillBeginDragging,
+    /// The content offset has changed.
+    DidScroll { offset: f32 },
+    /// The user lifted their finger after dragging the content.
+    ///
+    /// The velocity is the release velocity in points per millisecond, and the target is the
+    /// offset that the content is going to come to rest on. Observers can change the target,
+    /// and the content will come to rest on it instead, keeping the release velocity.
+    WillEndDragging { velocity: f32, target: f32 },
+    /// The content came to rest after decelerating or bouncing.
+    DidEndDecelerating,
+    /// The content came to rest after an animation started by `animate_to`.
+    DidEndScrollingAnimation,
+    /// The decelerating content reached the edge of the scrollable range and started
+    /// bouncing back, with the velocity that it hit the edge with.
+    DidBounce { velocity: f32 },
+}
+
+/// The identifier of an observer, which is used to remove it from the scroll controller.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ObserverId(pub(crate) u64);
+
+struct Observer {
+    id: ObserverId,
+    handler: Box<dyn FnMut(&mut ScrollEvent)>,
+}
+
+impl fmt::Debug for Observer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Observer").field(&self.id).finish()
+    }
+}
+
+/// A callback that is invoked once when a programmatic scroll animation ends.
+///
+/// The argument is `true` if the content reached the target, or `false` if the animation
+/// was interrupted.
+pub(crate) struct Completion(pub(crate) Box<dyn FnOnce(bool)>);
+
+impl fmt::Debug for Completion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Completion")
+    }
+}
+
+/// A headless scroll state machine along a single axis.
+///
+/// The controller receives touch events and the current time, and produces the content offset
+/// for each frame, including rubber banding while dragging, scroll inertia after the touch is
+/// released, and the edge bounce when the content goes beyond the scrollable range.
+///
+/// The content offset increases when the touch position decreases, just like `UIScrollView`.
+#[derive(Debug)]
+pub struct ScrollController {
+    content_length: f32,
+    viewport_length: f32,
+    content_inset: AxisInsets,
+    safe_area_inset: AxisInsets,
+    additional_inset: AxisInsets,
+    deceleration_rate: DecelerationRate,
+    bounce_response: f32,
+    scroll_enabled: bool,
+    bounces: bool,
+    always_bounce: bool,
+    paging: Option<Paging>,
+    snap_points: Option<SnapPoints>,
+    rubber_band: RubberBand<f32, Box<dyn RubberBandCurve<f32>>>,
+
+    phase: ScrollPhase,
+    animation: Animation,
+    offset: f32,
+    velocity: f32,
+
+    touch_began_time: f32,
+    touch_began_position: f32,
+    // The offset without rubber banding when the touch began.
+    drag_began_offset: f32,
+
+    animation_began_time: f32,
+    animation_began_offset: f32,
+    // The offset at which the decay animation comes to rest.
+    decay_target: f32,
+    // The offset that the spring back animation moves toward.
+    spring_target: f32,
+    spring_response: f32,
+    // The target that the spring moves toward from the next frame, after the scrollable range
+    // has changed.
+    pending_spring_target: Option<f32>,
+    completion: Option<Completion>,
+
+    observers: Vec<Observer>,
+    next_observer_id: u64,
+    // The offset that observers were last told about.
+    reported_offset: f32,
+
+    scroller: Scroller,
+    spring_back: SpringBack,
+    velocity_tracker: VelocityTracker,
+}
+
+impl ScrollController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The length of the content along the axis.
+    pub fn content_length(&self) -> f32 {
+        self.content_length
+    }
+
+    pub fn set_content_length(&mut self, content_length: f32) {
+        self.update_range(|controller| controller.content_length = content_length);
+    }
+
+    /// The length of the visible area along the axis.
+    ///
+    /// It is also used as the range of the rubber band effect.
+    pub fn viewport_length(&self) -> f32 {
+        self.viewport_length
+    }
+
+    pub fn set_viewport_length(&mut self, viewport_length: f32) {
+        self.update_range(|controller| controller.viewport_length = viewport_length);
+    }
+
+    /// The custom distance that the content is inset from the edges of the viewport.
+    pub fn content_inset(&self) -> AxisInsets {
+        self.content_inset
+    }
+
+    pub fn set_content_inset(&mut self, content_inset: AxisInsets) {
+        self.update_range(|controller| controller.content_inset = content_inset);
+    }
+
+    /// The insets of the viewport that are covered by system bars or rounded corners,
+    /// like `safeAreaInsets` of `UIView`.
+    pub fn safe_area_inset(&self) -> AxisInsets {
+        self.safe_area_inset
+    }
+
+    pub fn set_safe_area_inset(&mut self, safe_area_inset: AxisInsets) {
+        self.update_range(|controller| controller.safe_area_inset = safe_area_inset);
+    }
+
+    /// An extra inset that is managed by the front-end, for example to reveal a refresh control
+    /// while it is refreshing, without changing the custom content inset.
+    pub fn additional_inset(&self) -> AxisInsets {
+        self.additional_inset
+    }
+
+    pub fn set_additional_inset(&mut self, additional_inset: AxisInsets) {
+        self.update_range(|controller| controller.additional_inset = additional_inset);
+    }
+
+    /// The insets that determine the scrollable range, which is the sum of the content inset,
+    /// the safe area inset and the additional inset, like `adjustedContentInset` of
+    /// `UIScrollView`.
+    pub fn adjusted_content_inset(&self) -> AxisInsets {
+        self.content_inset + self.safe_area_inset + self.additional_inset
+    }
+
+    pub fn set_deceleration_rate(&mut self, deceleration_rate: DecelerationRate) {
+        self.deceleration_rate = deceleration_rate;
+    }
+
+    /// Sets the response of the spring that bounces the content back at the edge.
+    pub fn set_bounce_response(&mut self, bounce_response: f32) {
+        self.bounce_response = valid_response(bounce_response);
+    }
+
+    /// A Boolean value that determines whether scrolling is enabled.
+    ///
+    /// The default value is `true`.
+    pub fn is_scroll_enabled(&self) -> bool {
+        self.scroll_enabled
+    }
+
+    pub fn set_scroll_enabled(&mut self, scroll_enabled: bool) {
+        self.scroll_enabled = scroll_enabled;
+    }
+
+    /// A Boolean value that controls whether the content bounces past the edge of the
+    /// scrollable range and back again.
+    ///
+    /// When it is `false`, the content stops at the edge without rubber banding.
+    /// The default value is `true`.
+    pub fn bounces(&self) -> bool {
+        self.bounces
+    }
+
+    pub fn set_bounces(&mut self, bounces: bool) {
+        self.bounces = bounces;
+    }
+
+    /// A Boolean value that determines whether bouncing always occurs when scrolling reaches
+    /// the end of the content.
+    ///
+    /// The controller allows dragging even if the content is smaller than the viewport.
+    /// The default value is `false`.
+    pub fn always_bounce(&self) -> bool {
+        self.always_bounce
+    }
+
+    pub fn set_always_bounce(&mut self, always_bounce: bool) {
+        self.always_bounce = always_bounce;
+    }
+
+    /// The paging behavior of the controller.
+    ///
+    /// When it is set, the content comes to rest on multiples of the page size,
+    /// counted from the minimum offset. The default value is `None`.
+    pub fn paging(&self) -> Option<&Paging> {
+        self.paging.as_ref()
+    }
+
+    pub fn set_paging(&mut self, paging: Option<Paging>) {
+        self.paging = paging;
+    }
+
+    /// The offsets that the content is allowed to come to rest on.
+    ///
+    /// When it is set, the fling is projected to its natural resting point and adjusted
+    /// to land on the nearest snap point. Paging takes precedence over snap points.
+    /// The default value is `None`.
+    pub fn snap_points(&self) -> Option<&SnapPoints> {
+        self.snap_points.as_ref()
+    }
+
+    pub fn set_snap_points(&mut self, snap_points: Option<SnapPoints>) {
+        self.snap_points = snap_points;
+    }
+
+    /// The rubber band that resists dragging the content beyond the scrollable range.
+    ///
+    /// The range of the rubber band is the viewport length. The default value is the
+    /// rubber band of `UIScrollView`.
+    pub fn rubber_band(&self) -> &RubberBand<f32, Box<dyn RubberBandCurve<f32>>> {
+        &self.rubber_band
+    }
+
+    pub fn set_rubber_band<C: RubberBandCurve<f32> + 'static>(
+        &mut self,
+        rubber_band: RubberBand<f32, C>,
+    ) {
+        self.rubber_band = rubber_band.boxed();
+    }
+
+    /// Returns `true` if the content can be moved by the user along this axis.
+    pub fn can_scroll(&self) -> bool {
+        let inset = self.adjusted_content_inset();
+        self.scroll_enabled
+            && (self.always_bounce
+                || self.content_length + inset.leading + inset.trailing > self.viewport_length)
+    }
+
+    /// The minimum offset that the content can be scrolled to.
+    pub fn min_offset(&self) -> f32 {
+        -self.adjusted_content_inset().leading
+    }
+
+    /// The maximum offset that the content can be scrolled to.
+    ///
+    /// The value is never less than the minimum offset.
+    pub fn max_offset(&self) -> f32 {
+        let max =
+            self.content_length - self.viewport_length + self.adjusted_content_inset().trailing;
+        max.max(self.min_offset())
+    }
+
+    /// The current content offset.
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    /// Moves the content to the given offset immediately and stops any running animation.
+    pub fn set_offset(&mut self, offset: f32) {
+        self.stop_animation();
+        self.offset = offset;
+        self.notify_scroll();
+    }
+
+    /// Tells the controller that content of `delta` length was inserted at `at`, or removed
+    /// from it if `delta` is negative, measured along the content.
+    ///
+    /// If the change happens above the top of the viewport, the offset, the origin of the
+    /// running fling or drag and the target of the running spring are shifted together,
+    /// so the visible content does not jump and any animation continues seamlessly.
+    pub fn adjust_for_content_inserted(&mut self, at: f32, delta: f32) {
+        let shift = if delta >= 0.0 {
+            if at <= self.offset {
+                delta
+            } else {
+                0.0
+            }
+        } else {
+            // Only the removed part that is above the viewport moves the visible content.
+            -(self.offset - at).clamp(0.0, -delta)
+        };
+        self.shift_content(shift, delta);
+    }
+
+    /// Scales the content length by `ratio` around `focal`, a position in the viewport,
+    /// for example when the content is zoomed by a `ZoomController`.
+    ///
+    /// The content under `focal` stays in place, and any animation continues from there.
+    /// At rest, the content is clamped to the new scrollable range.
+    pub fn scale_content(&mut self, ratio: f32, focal: f32) {
+        if ratio <= 0.0 || !ratio.is_finite() {
+            return;
+        }
+        let shift = (self.offset + focal) * ratio - focal - self.offset;
+        self.shift_content(shift, self.content_length * (ratio - 1.0));
+    }
+
+    /// The velocity of the content at the last frame, in points per millisecond.
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    pub fn phase(&self) -> ScrollPhase {
+        self.phase
+    }
+
+    /// Returns `true` if the user has touched the content, even if they have not started dragging it.
+    pub fn is_tracking(&self) -> bool {
+        matches!(self.phase, ScrollPhase::Tracking | ScrollPhase::Dragging)
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.phase == ScrollPhase::Dragging
+    }
+
+    /// Returns `true` if the content is still moving after the user lifted their finger.
+    pub fn is_decelerating(&self) -> bool {
+        matches!(
+            self.phase,
+            ScrollPhase::Decelerating | ScrollPhase::Bouncing
+        )
+    }
+
+    /// Tells the controller that a touch began at the given time and position.
+    ///
+    /// Any running animation is interrupted and the content stays where it is.
+    pub fn touch_began(&mut self, time: f32, position: f32) {
+        self.stop_animation();
+        self.velocity_tracker.reset();
+        self.touch_began_time = time;
+        self.touch_began_position = position;
+        self.drag_began_offset = self.apply_rubber_band(self.offset, true);
+        self.velocity_tracker.add_data_point(0.0, position);
+        self.phase = ScrollPhase::Tracking;
+    }
+
+    /// Tells the controller that the touch moved to the given position.
+    pub fn touch_moved(&mut self, time: f32, position: f32) {
+        if !self.is_tracking() {
+            return;
+        }
+        let began_dragging = self.phase == ScrollPhase::Tracking;
+        self.phase = ScrollPhase::Dragging;
+        self.velocity_tracker
+            .add_data_point(time - self.touch_began_time, position);
+        if !self.can_scroll() {
+            return;
+        }
+        if began_dragging {
+            self.emit(ScrollEvent::WillBeginDragging);
+        }
+        let translation = position - self.touch_began_position;
+        self.offset = self.apply_rubber_band(self.drag_began_offset - translation, false);
+        self.notify_scroll();
+    }
+
+    /// Tells the controller that the touch was lifted or cancelled at the given time.
+    ///
+    /// The content starts decelerating with the velocity of the gesture,
+    /// or springs back if it is beyond the scrollable range.
+    pub fn touch_ended(&mut self, time: f32) {
+        let mut velocity = self.release_velocity(time);
+        if VelocityTracker::approaching_halt(velocity, 0.0) {
+            velocity = 0.0;
+        }
+        self.release(time, velocity);
+    }
+
+    /// Animates the content to the given offset with a spring, starting at the given time.
+    ///
+    /// The target is clamped to the scrollable range. Any running animation or touch
+    /// interaction is interrupted, and the content starts moving from its current offset
+    /// with its current velocity. The further the content has to move, the longer it takes.
+    pub fn animate_to(&mut self, now: f32, offset: f32) {
+        self.animate_to_with_completion(now, offset, |_| {});
+    }
+
+    /// Animates the content to the given offset like `animate_to`, and calls `completion`
+    /// once the animation ends.
+    ///
+    /// The completion receives `true` if the content came to rest on the target, or `false`
+    /// if the animation was interrupted by a touch, another animation or `set_offset`.
+    pub fn animate_to_with_completion<F>(&mut self, now: f32, offset: f32, completion: F)
+    where
+        F: FnOnce(bool) + 'static,
+    {
+        // Catches up with the running animation so that the spring starts from the current state.
+        self.tick(now);
+        let velocity = if self.animation == Animation::None {
+            0.0
+        } else {
+            self.velocity
+        };
+        self.stop_animation();
+
+        let target = offset.clamp(self.min_offset(), self.max_offset());
+        let response = self.animation_response((target - self.offset).abs());
+        self.begin_spring(now, velocity, target, response);
+        self.phase = ScrollPhase::Animating;
+        self.completion = Some(Completion(Box::new(completion)));
+    }
+
+    /// Advances the animation to the given time and returns the content offset at that moment.
+    pub fn tick(&mut self, now: f32) -> f32 {
+        match self.animation {
+            Animation::Decay => self.tick_decelerating(now),
+            Animation::Spring => self.tick_spring(now),
+            Animation::None => {}
+        }
+        self.notify_scroll();
+        self.offset
+    }
+
+    /// Registers a handler that receives the scroll events of the controller,
+    /// and returns the identifier to remove it with.
+    ///
+    /// Handlers are called in the order that they were added. They receive the event mutably,
+    /// so that they can adjust the target of `ScrollEvent::WillEndDragging`.
+    pub fn add_observer<F>(&mut self, handler: F) -> ObserverId
+    where
+        F: FnMut(&mut ScrollEvent) + 'static,
+    {
+        let id = ObserverId(self.next_observer_id);
+        self.next_observer_id += 1;
+        self.observers.push(Observer {
+            id,
+            handler: Box::new(handler),
+        });
+        id
+    }
+
+    /// Removes a handler added by `add_observer`. Returns `false` if it has been removed already.
+    pub fn remove_observer(&mut self, id: ObserverId) -> bool {
+        let count = self.observers.len();
+        self.observers.retain(|observer| observer.id != id);
+        self.observers.len() != count
+    }
+}
+
+impl ScrollController {
+    /// The velocity of the content when the touch is released, in points per millisecond.
+    pub(crate) fn release_velocity(&self, time: f32) -> f32 {
+        if !self.is_tracking() || !self.can_scroll() {
+            return 0.0;
+        }
+        // The direction of the gesture velocity is opposite to the sign of the content offset change.
+        -self
+            .velocity_tracker
+            .calculate_at(time - self.touch_began_time)
+    }
+
+    /// Ends the touch interaction and starts animating with the given velocity.
+    pub(crate) fn release(&mut self, time: f32, velocity: f32) {
+        if !self.is_tracking() {
+            return;
+        }
+        let was_dragging = self.is_dragging() && self.can_scroll();
+        self.begin_release_animation(time, velocity);
+        if !was_dragging {
+            return;
+        }
+
+        let target = self.resting_offset();
+        let mut event = ScrollEvent::WillEndDragging { velocity, target };
+        self.emit_mut(&mut event);
+        if let ScrollEvent::WillEndDragging {
+            target: adjusted_target,
+            ..
+        } = event
+        {
+            if adjusted_target != target {
+                self.redirect(time, adjusted_target);
+            }
+        }
+    }
+
+    fn begin_release_animation(&mut self, time: f32, mut velocity: f32) {
+        if !self.bounces {
+            // The content cannot move beyond the edge that it is pinned against.
+            let pinned = (self.offset <= self.min_offset() && velocity < 0.0)
+                || (self.offset >= self.max_offset() && velocity > 0.0);
+            if pinned {
+                velocity = 0.0;
+            }
+        }
+        if let Some(paging) = self.paging {
+            self.begin_paging(time, velocity, &paging);
+            return;
+        }
+        let overflow = self.overflow();
+        if overflow != 0.0 {
+            // When released, the content offset has exceeded the boundary.
+            // The gesture velocity is measured before rubber banding, so it has to be converted
+            // to the velocity that the content is displayed with.
+            velocity *= self.rubber_band_derivative(overflow);
+            let overflow_velocity = overflow / OVERFLOW_VELOCITY_DIVISOR;
+            // If two velocities are in opposite directions, add the two velocities.
+            if overflow_velocity.is_sign_negative() != velocity.is_sign_negative() {
+                velocity += overflow_velocity;
+            } else {
+                velocity = overflow_velocity;
+            }
+            self.begin_bouncing(time, velocity);
+        } else if let Some(trajectory) = self.snap_trajectory(velocity) {
+            match trajectory {
+                SnapTrajectory::Decay {
+                    target,
+                    deceleration_rate,
+                } => {
+                    self.begin_decelerating(time, velocity, deceleration_rate);
+                    // Avoids the rounding error of the deceleration rate.
+                    self.decay_target = target;
+                }
+                SnapTrajectory::Spring { target } => {
+                    let response = self
+                        .snap_points
+                        .as_ref()
+                        .map_or(self.bounce_response, SnapPoints::response);
+                    self.begin_spring(time, velocity, target, response);
+                    self.phase = ScrollPhase::Decelerating;
+                }
+            }
+        } else if velocity != 0.0 {
+            self.begin_decelerating(time, velocity, self.deceleration_rate);
+        } else {
+            self.finish_animation();
+        }
+    }
+
+    fn snap_trajectory(&self, velocity: f32) -> Option<SnapTrajectory> {
+        let min = self.min_offset();
+        let max = self.max_offset();
+        let trajectory =
+            self.snap_points
+                .as_ref()?
+                .trajectory(self.offset, velocity, self.deceleration_rate)?;
+        let target = trajectory.target();
+        if target < min || target > max {
+            // The snap point cannot be reached, rest on the edge instead.
+            return Some(SnapTrajectory::Spring {
+                target: target.clamp(min, max),
+            });
+        }
+        if target == self.offset && velocity == 0.0 {
+            return None;
+        }
+        Some(trajectory)
+    }
+
+    fn begin_decelerating(&mut self, time: f32, velocity: f32, rate: DecelerationRate) {
+        self.scroller.set_deceleration_rate(rate);
+        self.scroller.fling(velocity);
+        self.animation_began_time = time;
+        self.animation_began_offset = self.offset;
+        self.decay_target = self.offset + self.scroller.final_offset();
+        self.velocity = velocity;
+        self.phase = ScrollPhase::Decelerating;
+        self.animation = Animation::Decay;
+    }
+
+    fn tick_decelerating(&mut self, now: f32) {
+        let velocity = if let Some(value) = self.scroller.value(now - self.animation_began_time) {
+            self.offset = self.animation_began_offset + value.offset;
+            self.velocity = value.velocity;
+            value.velocity
+        } else {
+            // Lands exactly on the resting point that the fling was heading to.
+            self.offset = self.decay_target;
+            if self.overflow() == 0.0 {
+                self.finish_animation();
+                return;
+            }
+            // The scrollable range has shrunk during the fling, so the resting point is beyond it.
+            0.0
+        };
+
+        let overflow = self.overflow();
+        if overflow == 0.0 {
+            return;
+        }
+        if self.bounces {
+            // When scrolling to the edge, if there is still unused velocity, a spring back will occur.
+            self.begin_bouncing(now, velocity);
+            self.notify_scroll();
+            self.emit(ScrollEvent::DidBounce { velocity });
+        } else {
+            self.offset -= overflow;
+            self.finish_animation();
+        }
+    }
+
+    fn begin_bouncing(&mut self, time: f32, velocity: f32) {
+        let target = if self.offset < self.min_offset() {
+            self.min_offset()
+        } else {
+            self.max_offset()
+        };
+        self.begin_spring(time, velocity, target, self.bounce_response);
+        self.phase = ScrollPhase::Bouncing;
+    }
+
+    fn begin_paging(&mut self, time: f32, velocity: f32, paging: &Paging) {
+        let min = self.min_offset();
+        let target =
+            (min + paging.target_offset(self.offset - min, velocity)).clamp(min, self.max_offset());
+        let overflow = self.overflow();
+        self.begin_spring(time, velocity, target, paging.response());
+        self.phase = if overflow != 0.0 {
+            ScrollPhase::Bouncing
+        } else {
+            ScrollPhase::Decelerating
+        };
+    }
+
+    /// Starts moving the content toward the target with a spring.
+    ///
+    /// The caller is responsible for setting the phase that the spring represents.
+    fn begin_spring(&mut self, time: f32, velocity: f32, target: f32, response: f32) {
+        self.spring_target = target;
+        self.spring_response = response;
+        self.pending_spring_target = None;
+        self.spring_back.reset();
+        self.spring_back
+            .absorb_with_response(velocity, self.offset - target, response);
+        self.animation_began_time = time;
+        self.velocity = velocity;
+        self.animation = Animation::Spring;
+    }
+
+    fn tick_spring(&mut self, now: f32) {
+        let elapsed = now - self.animation_began_time;
+        if let Some(value) = self.spring_back.value(elapsed) {
+            self.offset = self.spring_target + value.offset;
+            self.velocity = value.velocity;
+        } else {
+            self.offset = self.spring_target;
+            self.velocity = 0.0;
+            if self.pending_spring_target.is_none() {
+                self.finish_animation();
+                return;
+            }
+        }
+        if let Some(target) = self.pending_spring_target {
+            // Starts from the current offset and velocity, so the motion stays continuous.
+            self.begin_spring(now, self.velocity, target, self.spring_response);
+        }
+    }
+
+    /// Moves the content by `shift` together with the running drag or animation,
+    /// and changes the content length by `delta`.
+    ///
+    /// Both happen inside `update_range`, so the origin of a running drag is compensated
+    /// with offsets that are measured against the same range.
+    fn shift_content(&mut self, shift: f32, delta: f32) {
+        self.update_range(|controller| {
+            controller.offset += shift;
+            controller.animation_began_offset += shift;
+            controller.decay_target += shift;
+            controller.spring_target += shift;
+            if let Some(target) = controller.pending_spring_target.as_mut() {
+                *target += shift;
+            }
+            controller.content_length += delta;
+        });
+        self.notify_scroll();
+    }
+
+    /// Changes the scrollable range with `update`, and adapts the content to the new range.
+    ///
+    /// The content at rest is clamped to the range. While dragging, the displayed offset is kept
+    /// and the following movement is rubber banded against the new range. A fling keeps
+    /// decelerating and bounces if it goes beyond the new range, and a spring is redirected
+    /// to the new range on the next frame.
+    fn update_range<F: FnOnce(&mut Self)>(&mut self, update: F) {
+        let pulled_offset = self.apply_rubber_band(self.offset, true);
+        update(self);
+
+        let min = self.min_offset();
+        let max = self.max_offset();
+        match self.phase {
+            ScrollPhase::Idle => {
+                self.offset = self.offset.clamp(min, max);
+                self.notify_scroll();
+            }
+            ScrollPhase::Tracking | ScrollPhase::Dragging => {
+                self.drag_began_offset += self.apply_rubber_band(self.offset, true) - pulled_offset;
+            }
+            _ if self.animation == Animation::Spring => {
+                let overflow = self.overflow();
+                let target = if self.phase == ScrollPhase::Bouncing && overflow != 0.0 {
+                    // Keeps bouncing back to the edge that the content is beyond.
+                    if overflow < 0.0 {
+                        min
+                    } else {
+                        max
+                    }
+                } else {
+                    self.spring_target.clamp(min, max)
+                };
+                self.pending_spring_target = (target != self.spring_target).then_some(target);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the spring response of a programmatic scroll animation over the given distance.
+    fn animation_response(&self, distance: f32) -> f32 {
+        if self.viewport_length <= 0.0 {
+            return DEFAULT_RESPONSE;
+        }
+        let progress = (distance / self.viewport_length).min(1.0);
+        MIN_ANIMATION_RESPONSE + (DEFAULT_RESPONSE - MIN_ANIMATION_RESPONSE) * progress
+    }
+
+    fn finish_animation(&mut self) {
+        let phase = self.phase;
+        self.reset_animation();
+        self.notify_scroll();
+        if let Some(Completion(completion)) = self.completion.take() {
+            completion(true);
+        }
+        match phase {
+            ScrollPhase::Decelerating | ScrollPhase::Bouncing => {
+                self.emit(ScrollEvent::DidEndDecelerating)
+            }
+            ScrollPhase::Animating => self.emit(ScrollEvent::DidEndScrollingAnimation),
+            _ => {}
+        }
+    }
+
+    /// Interrupts the running animation without telling the observers that it ended.
+    fn stop_animation(&mut self) {
+        self.scroller.reset();
+        self.spring_back.reset();
+        if let Some(Completion(completion)) = self.completion.take() {
+            completion(false);
+        }
+        if self.animation != Animation::None {
+            self.reset_animation();
+        }
+    }
+
+    fn reset_animation(&mut self) {
+        self.velocity = 0.0;
+        self.phase = ScrollPhase::Idle;
+        self.animation = Animation::None;
+        self.pending_spring_target = None;
+    }
+
+    /// The offset that the running animation is going to come to rest on.
+    fn resting_offset(&self) -> f32 {
+        match self.animation {
+            // The content springs back to the edge if the fling goes beyond it.
+            Animation::Decay => self
+                .decay_target
+                .clamp(self.min_offset(), self.max_offset()),
+            Animation::Spring => self.spring_target,
+            Animation::None => self.offset,
+        }
+    }
+
+    /// Makes the animation started on release come to rest on `target` instead,
+    /// keeping the current velocity.
+    fn redirect(&mut self, time: f32, target: f32) {
+        let target = target.clamp(self.min_offset(), self.max_offset());
+        let velocity = self.velocity;
+        self.scroller.reset();
+        self.spring_back.reset();
+        if self.overflow() == 0.0
+            && velocity != 0.0
+            && self.scroller.fling_to(velocity, target - self.offset)
+        {
+            self.animation_began_time = time;
+            self.animation_began_offset = self.offset;
+            self.decay_target = target;
+            self.animation = Animation::Decay;
+        } else {
+            self.begin_spring(time, velocity, target, self.bounce_response);
+        }
+        self.phase = ScrollPhase::Decelerating;
+    }
+
+    fn emit(&mut self, mut event: ScrollEvent) {
+        self.emit_mut(&mut event);
+    }
+
+    fn emit_mut(&mut self, event: &mut ScrollEvent) {
+        for observer in &mut self.observers {
+            (observer.handler)(event);
+        }
+    }
+
+    /// Sends `ScrollEvent::DidScroll` if the offset has changed since the last time.
+    fn notify_scroll(&mut self) {
+        if self.offset != self.reported_offset {
+            self.reported_offset = self.offset;
+            self.emit(ScrollEvent::DidScroll {
+                offset: self.offset,
+            });
+        }
+    }
+
+    /// The distance by which the content offset exceeds the scrollable range.
+    ///
+    /// The value is negative when the offset is less than the minimum offset,
+    /// and positive when it is greater than the maximum offset.
+    fn overflow(&self) -> f32 {
+        let min = self.min_offset();
+        let max = self.max_offset();
+        if self.offset < min {
+            self.offset - min
+        } else if self.offset > max {
+            self.offset - max
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the ratio of the displayed velocity to the gesture velocity when the content
+    /// is rubber banded by `overflow` beyond the edge.
+    fn rubber_band_derivative(&self, overflow: f32) -> f32 {
+        let range = self.viewport_length;
+        if range.abs() < f32::EPSILON {
+            return 1.0;
+        }
+        let pulled = self.rubber_band.calculate_offset_inv(overflow.abs(), range);
+        self.rubber_band.calculate_derivative(pulled, range)
+    }
+
+    fn apply_rubber_band(&self, offset: f32, inverse: bool) -> f32 {
+        let min = self.min_offset();
+        let max = self.max_offset();
+        if min <= offset && offset <= max {
+            return offset;
+        }
+        if !self.bounces {
+            return offset.clamp(min, max);
+        }
+
+        let range = self.viewport_length;
+        if range.abs() < f32::EPSILON {
+            return offset;
+        }
+
+        let target = if offset < min { min } else { max };
+        let distance = offset - target;
+        let transformed = if inverse {
+            self.rubber_band.calculate_offset_inv(distance.abs(), range)
+        } else {
+            self.rubber_band.calculate_offset(distance.abs(), range)
+        };
+        target + transformed * distance.signum()
+    }
+}
+
+impl Default for ScrollController {
+    fn default() -> Self {
+        Self {
+            content_length: 0.0,
+            viewport_length: 0.0,
+            content_inset: AxisInsets::ZERO,
+            safe_area_inset: AxisInsets::ZERO,
+            additional_inset: AxisInsets::ZERO,
+            deceleration_rate: DecelerationRate::NORMAL,
+            bounce_response: DEFAULT_RESPONSE,
+            scroll_enabled: true,
+            bounces: true,
+            always_bounce: false,
+            paging: None,
+            snap_points: None,
+            rubber_band: RubberBand::<f32>::default().boxed(),
+            phase: ScrollPhase::Idle,
+            animation: Animation::None,
+            offset: 0.0,
+            velocity: 0.0,
+            touch_began_time: 0.0,
+            touch_began_position: 0.0,
+            drag_began_offset: 0.0,
+            animation_began_time: 0.0,
+            animation_began_offset: 0.0,
+            decay_target: 0.0,
+            spring_target: 0.0,
+            spring_response: DEFAULT_RESPONSE,
+            pending_spring_target: None,
+            completion: None,
+            observers: Vec::new(),
+            next_observer_id: 0,
+            reported_offset: 0.0,
+            scroller: Scroller::default(),
+            spring_back: SpringBack::new(),
+            velocity_tracker: VelocityTracker::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, vec, vec::Vec};
+    use core::cell::{Cell, RefCell};
+
+    use crate::{
+        geometry::AxisInsets, paging::Paging, rubber_band::RubberBand, snapping::SnapPoints,
+    };
+
+    use super::{ScrollController, ScrollEvent, ScrollPhase};
+
+    fn controller() -> ScrollController {
+        let mut controller = ScrollController::new();
+        controller.set_content_length(2000.0);
+        controller.set_viewport_length(600.0);
+        controller
+    }
+
+    fn settle(controller: &mut ScrollController, mut now: f32) -> f32 {
+        while controller.phase() != ScrollPhase::Idle {
+            now += 16.0;
+            controller.tick(now);
+        }
+        now
+    }
+
+    fn drag(controller: &mut ScrollController, from: f32, to: f32, duration: f32) {
+        controller.touch_began(0.0, from);
+        let steps = 10;
+        for i in 1..=steps {
+            let progress = i as f32 / steps as f32;
+            controller.touch_moved(duration * progress, from + (to - from) * progress);
+        }
+        controller.touch_ended(duration);
+    }
+
+    #[test]
+    fn test_fling_decelerates() {
+        let mut controller = controller();
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        assert_eq!(controller.phase(), ScrollPhase::Decelerating);
+        assert!(controller.velocity() > 0.0);
+
+        let mut now = 100.0;
+        let mut previous = controller.offset();
+        while controller.phase() == ScrollPhase::Decelerating {
+            now += 16.0;
+            let offset = controller.tick(now);
+            assert!(offset >= previous);
+            previous = offset;
+        }
+        assert_eq!(controller.phase(), ScrollPhase::Idle);
+        assert!(controller.offset() > 100.0 && controller.offset() <= controller.max_offset());
+    }
+
+    #[test]
+    fn test_hold_before_release_does_not_fling() {
+        let mut controller = controller();
+        controller.touch_began(0.0, 500.0);
+        for i in 1..=10 {
+            let t = i as f32 * 10.0;
+            controller.touch_moved(t, 500.0 - t);
+        }
+        // The finger rests without reporting any movement before it is lifted.
+        controller.touch_ended(600.0);
+        assert_eq!(controller.phase(), ScrollPhase::Idle);
+        assert_eq!(controller.velocity(), 0.0);
+        assert_eq!(controller.offset(), 100.0);
+    }
+
+    #[test]
+    fn test_overscroll_springs_back() {
+        let mut controller = controller();
+        drag(&mut controller, 100.0, 400.0, 1000.0);
+        // The offset is rubber banded while dragging beyond the minimum offset.
+        assert!(controller.offset() < 0.0 && controller.offset() > -300.0);
+        assert_eq!(controller.phase(), ScrollPhase::Bouncing);
+
+        settle(&mut controller, 1000.0);
+        assert_eq!(controller.offset(), controller.min_offset());
+    }
+
+    #[test]
+    fn test_overscroll_release_velocity_is_rubber_banded() {
+        let mut controller = controller();
+        // Pulls beyond the minimum offset, then flicks back toward the edge.
+        controller.touch_began(0.0, 100.0);
+        for i in 1..=10 {
+            controller.touch_moved(i as f32 * 10.0, 100.0 + i as f32 * 30.0);
+        }
+        for i in 1..=4 {
+            controller.touch_moved(100.0 + i as f32 * 10.0, 400.0 - i as f32 * 20.0);
+        }
+        let gesture_velocity = controller.release_velocity(140.0);
+        let overflow = controller.overflow();
+        assert!(gesture_velocity > 0.0 && overflow < 0.0);
+        controller.touch_ended(140.0);
+
+        // The content moves slower than the finger beyond the edge.
+        let displayed_velocity = gesture_velocity * controller.rubber_band_derivative(overflow);
+        assert!(displayed_velocity.abs() < gesture_velocity.abs());
+        let expected = displayed_velocity + overflow / 100.0;
+        assert!((controller.velocity() - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_overscroll_release_with_zero_coefficient() {
+        let mut controller = controller();
+        controller.touch_began(0.0, 100.0);
+        for i in 1..=10 {
+            controller.touch_moved(i as f32 * 10.0, 100.0 + i as f32 * 30.0);
+        }
+        for i in 1..=4 {
+            controller.touch_moved(100.0 + i as f32 * 10.0, 400.0 - i as f32 * 20.0);
+        }
+        // The content no longer follows the touch beyond the edge.
+        controller.set_rubber_band(RubberBand::with_coefficient(0.0));
+        controller.touch_ended(140.0);
+        assert!(controller.velocity().is_finite());
+
+        let mut now = 140.0;
+        while controller.phase() == ScrollPhase::Bouncing {
+            now += 16.0;
+            controller.tick(now);
+            assert!(controller.offset().is_finite());
+        }
+        assert_eq!(controller.offset(), controller.min_offset());
+    }
+
+    #[test]
+    fn test_adjusted_content_inset() {
+        let mut controller = controller();
+        controller.set_content_inset(AxisInsets::new(10.0, 20.0));
+        controller.set_safe_area_inset(AxisInsets::new(44.0, 34.0));
+        assert_eq!(
+            controller.adjusted_content_inset(),
+            AxisInsets::new(54.0, 54.0)
+        );
+        assert_eq!(controller.min_offset(), -54.0);
+        assert_eq!(controller.max_offset(), 1454.0);
+
+        // Reveals a refresh control above the content.
+        controller.set_additional_inset(AxisInsets::new(60.0, 0.0));
+        assert_eq!(controller.min_offset(), -114.0);
+        drag(&mut controller, 100.0, 400.0, 1000.0);
+        settle(&mut controller, 1000.0);
+        assert_eq!(controller.offset(), -114.0);
+    }
+
+    #[test]
+    fn test_range_changes_while_animating() {
+        // The content shrinks during a fling and the fling goes beyond the new range.
+        let mut controller = controller();
+        controller.set_offset(1000.0);
+        drag(&mut controller, 500.0, 300.0, 100.0);
+        assert_eq!(controller.phase(), ScrollPhase::Decelerating);
+        controller.tick(116.0);
+        let offset = controller.offset();
+        controller.set_content_length(1200.0);
+        assert_eq!(controller.offset(), offset);
+        settle(&mut controller, 116.0);
+        assert_eq!(controller.offset(), 600.0);
+
+        // An inset is added above the content while it is bouncing back to the top.
+        drag(&mut controller, 0.0, 900.0, 100.0);
+        assert_eq!(controller.phase(), ScrollPhase::Bouncing);
+        controller.tick(116.0);
+        controller.set_additional_inset(AxisInsets::new(60.0, 0.0));
+        settle(&mut controller, 116.0);
+        assert_eq!(controller.offset(), -60.0);
+
+        // The content at rest is clamped to the new range.
+        controller.set_content_length(400.0);
+        assert_eq!(controller.offset(), -60.0);
+        controller.set_offset(0.0);
+        controller.set_viewport_length(300.0);
+        assert_eq!(controller.offset(), 0.0);
+        controller.set_additional_inset(AxisInsets::ZERO);
+        controller.set_offset(100.0);
+        controller.set_content_length(350.0);
+        assert_eq!(controller.offset(), 50.0);
+    }
+
+    #[test]
+    fn test_adjust_for_content_inserted() {
+        let mut controller = controller();
+        controller.set_offset(800.0);
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        controller.tick(116.0);
+        let offset = controller.offset();
+        let velocity = controller.velocity();
+        let target = controller.resting_offset();
+
+        // Inserts items above the viewport in the middle of the fling.
+        controller.adjust_for_content_inserted(100.0, 300.0);
+        assert_eq!(controller.content_length(), 2300.0);
+        assert_eq!(controller.offset(), offset + 300.0);
+        assert_eq!(controller.resting_offset(), target + 300.0);
+        controller.tick(132.0);
+        assert!((controller.offset() - offset - 300.0 - velocity * 16.0).abs() < 2.0);
+        assert_eq!(controller.phase(), ScrollPhase::Decelerating);
+
+        // Items below the viewport do not move the content.
+        let offset = controller.offset();
+        controller.adjust_for_content_inserted(2200.0, 100.0);
+        assert_eq!(controller.offset(), offset);
+
+        // Only the part of the removed items that is above the viewport moves the content.
+        controller.adjust_for_content_inserted(offset - 50.0, -200.0);
+        assert_eq!(controller.offset(), offset - 50.0);
+    }
+
+    #[test]
+    fn test_content_inserted_while_dragging_at_the_edge() {
+        let mut controller = controller();
+        controller.touch_began(0.0, 1500.0);
+        controller.touch_moved(16.0, 0.0);
+        let max_offset = controller.max_offset();
+        assert!(controller.offset() > max_offset);
+
+        // Inserts items above the viewport while the content is pulled beyond the bottom edge.
+        let offset = controller.offset();
+        controller.adjust_for_content_inserted(100.0, 300.0);
+        assert_eq!(controller.offset(), offset + 300.0);
+        assert_eq!(controller.max_offset(), max_offset + 300.0);
+
+        // The next move is still rubber banded from the displayed offset.
+        controller.touch_moved(32.0, -1.0);
+        let delta = controller.offset() - offset - 300.0;
+        assert!(delta > 0.0 && delta < 1.0);
+    }
+
+    #[test]
+    fn test_scale_content_while_dragging() {
+        let mut controller = ScrollController::new();
+        controller.set_content_length(1000.0);
+        controller.set_viewport_length(600.0);
+        controller.touch_began(0.0, 500.0);
+        controller.touch_moved(16.0, 200.0);
+        assert_eq!(controller.offset(), 300.0);
+
+        // The content under the focal point stays in place.
+        controller.scale_content(1.5, 300.0);
+        assert_eq!(controller.offset(), 600.0);
+        assert_eq!(controller.content_length(), 1500.0);
+
+        // The drag continues from there.
+        controller.touch_moved(32.0, 199.0);
+        assert!((controller.offset() - 601.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_scroll_disabled_without_always_bounce() {
+        let mut controller = controller();
+        controller.set_content_length(400.0);
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        assert_eq!(controller.offset(), 0.0);
+        assert_eq!(controller.phase(), ScrollPhase::Idle);
+
+        controller.set_always_bounce(true);
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        assert!(controller.offset() > 0.0);
+        assert_eq!(controller.phase(), ScrollPhase::Bouncing);
+    }
+
+    #[test]
+    fn test_paging() {
+        let mut controller = controller();
+        controller.set_paging(Some(Paging::new(600.0)));
+        // A quick swipe that moves less than half a page still advances to the next page.
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        assert_eq!(controller.phase(), ScrollPhase::Decelerating);
+
+        settle(&mut controller, 100.0);
+        assert_eq!(controller.offset(), 600.0);
+    }
+
+    #[test]
+    fn test_snap_points() {
+        let mut controller = controller();
+        controller.set_snap_points(Some(SnapPoints::new(vec![0.0, 250.0, 500.0, 750.0])));
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        assert_eq!(controller.phase(), ScrollPhase::Decelerating);
+
+        settle(&mut controller, 100.0);
+        assert!(controller
+            .snap_points()
+            .unwrap()
+            .offsets()
+            .contains(&controller.offset()));
+    }
+
+    #[test]
+    fn test_animate_to() {
+        let mut controller = controller();
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        controller.tick(150.0);
+        let velocity = controller.velocity();
+
+        // Interrupts the fling and keeps its velocity.
+        let finished = Rc::new(Cell::new(None));
+        let result = finished.clone();
+        controller.animate_to_with_completion(150.0, 5000.0, move |f| result.set(Some(f)));
+        assert_eq!(controller.phase(), ScrollPhase::Animating);
+        assert_eq!(controller.velocity(), velocity);
+
+        // A touch interrupts the animation.
+        controller.touch_began(200.0, 0.0);
+        assert_eq!(finished.get(), Some(false));
+
+        let finished = Rc::new(Cell::new(None));
+        let result = finished.clone();
+        controller.animate_to_with_completion(200.0, 5000.0, move |f| result.set(Some(f)));
+        settle(&mut controller, 200.0);
+        // The target is clamped to the scrollable range.
+        assert_eq!(controller.offset(), controller.max_offset());
+        assert_eq!(finished.get(), Some(true));
+    }
+
+    #[test]
+    fn test_scroll_events() {
+        let mut controller = controller();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        let observer = controller.add_observer(move |event| {
+            // Records the kind of every event except the offset of `DidScroll`.
+            let event = match *event {
+                ScrollEvent::DidScroll { .. } => ScrollEvent::DidScroll { offset: 0.0 },
+                event => event,
+            };
+            if recorded.borrow().last() != Some(&event) {
+                recorded.borrow_mut().push(event);
+            }
+        });
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        settle(&mut controller, 100.0);
+
+        let events = events.borrow();
+        assert_eq!(events[0], ScrollEvent::WillBeginDragging);
+        assert_eq!(events[1], ScrollEvent::DidScroll { offset: 0.0 });
+        assert!(
+            matches!(events[2], ScrollEvent::WillEndDragging { velocity, target }
+            if velocity > 0.0 && target > 100.0)
+        );
+        assert_eq!(events[3], ScrollEvent::DidScroll { offset: 0.0 });
+        assert_eq!(events[4], ScrollEvent::DidEndDecelerating);
+        assert_eq!(events.len(), 5);
+        assert!(controller.remove_observer(observer));
+        assert!(!controller.remove_observer(observer));
+    }
+
+    #[test]
+    fn test_adjust_target() {
+        let mut controller = controller();
+        controller.add_observer(|event| {
+            if let ScrollEvent::WillEndDragging { target, .. } = event {
+                *target = 600.0;
+            }
+        });
+        let bounced = Rc::new(Cell::new(false));
+        let result = bounced.clone();
+        controller.add_observer(move |event| {
+            if let ScrollEvent::DidBounce { .. } = event {
+                result.set(true);
+            }
+        });
+        drag(&mut controller, 500.0, 400.0, 100.0);
+        settle(&mut controller, 100.0);
+        assert_eq!(controller.offset(), 600.0);
+        assert!(!bounced.get());
+    }
+}
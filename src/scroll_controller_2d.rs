@@ -0,0 +1,466 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::boxed::Box;
+
+use crate::{
+    directional_lock::DirectionalLock,
+    geometry::{EdgeInsets, Point, Size},
+    rubber_band::{RubberBand, RubberBandCurve},
+    scroll_controller::{Completion, ScrollController, ScrollPhase},
+    scroller::DecelerationRate,
+    velocity_tracker::VelocityTracker,
+};
+
+/// A headless scroll state machine that moves the content along both axes.
+///
+/// It composes a horizontal and a vertical [`ScrollController`], which can be accessed
+/// individually to configure per-axis behaviors such as enabling scrolling and bouncing.
+#[derive(Debug, Default)]
+pub struct ScrollController2D {
+    horizontal: ScrollController,
+    vertical: ScrollController,
+    // The completion of the programmatic scroll animation, which ends when both axes stop.
+    completion: Option<Completion>,
+    directional_lock: Option<DirectionalLock>,
+    touch_began_position: Point,
+}
+
+impl ScrollController2D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The controller that simulates the horizontal axis.
+    pub fn horizontal(&self) -> &ScrollController {
+        &self.horizontal
+    }
+
+    pub fn horizontal_mut(&mut self) -> &mut ScrollController {
+        &mut self.horizontal
+    }
+
+    /// The controller that simulates the vertical axis.
+    pub fn vertical(&self) -> &ScrollController {
+        &self.vertical
+    }
+
+    pub fn vertical_mut(&mut self) -> &mut ScrollController {
+        &mut self.vertical
+    }
+
+    /// The size of the content.
+    pub fn content_size(&self) -> Size {
+        Size::new(
+            self.horizontal.content_length(),
+            self.vertical.content_length(),
+        )
+    }
+
+    pub fn set_content_size(&mut self, content_size: Size) {
+        self.horizontal.set_content_length(content_size.width);
+        self.vertical.set_content_length(content_size.height);
+    }
+
+    /// The size of the visible area.
+    pub fn viewport_size(&self) -> Size {
+        Size::new(
+            self.horizontal.viewport_length(),
+            self.vertical.viewport_length(),
+        )
+    }
+
+    pub fn set_viewport_size(&mut self, viewport_size: Size) {
+        self.horizontal.set_viewport_length(viewport_size.width);
+        self.vertical.set_viewport_length(viewport_size.height);
+    }
+
+    /// The custom distance that the content is inset from the edges of the viewport.
+    pub fn content_inset(&self) -> EdgeInsets {
+        EdgeInsets::from_axes(
+            self.horizontal.content_inset(),
+            self.vertical.content_inset(),
+        )
+    }
+
+    pub fn set_content_inset(&mut self, content_inset: EdgeInsets) {
+        self.horizontal
+            .set_content_inset(content_inset.horizontal());
+        self.vertical.set_content_inset(content_inset.vertical());
+    }
+
+    /// The insets of the viewport that are covered by system bars or rounded corners,
+    /// like `safeAreaInsets` of `UIView`.
+    pub fn safe_area_inset(&self) -> EdgeInsets {
+        EdgeInsets::from_axes(
+            self.horizontal.safe_area_inset(),
+            self.vertical.safe_area_inset(),
+        )
+    }
+
+    pub fn set_safe_area_inset(&mut self, safe_area_inset: EdgeInsets) {
+        self.horizontal
+            .set_safe_area_inset(safe_area_inset.horizontal());
+        self.vertical
+            .set_safe_area_inset(safe_area_inset.vertical());
+    }
+
+    /// An extra inset that is managed by the front-end, for example to reveal a refresh control.
+    pub fn additional_inset(&self) -> EdgeInsets {
+        EdgeInsets::from_axes(
+            self.horizontal.additional_inset(),
+            self.vertical.additional_inset(),
+        )
+    }
+
+    pub fn set_additional_inset(&mut self, additional_inset: EdgeInsets) {
+        self.horizontal
+            .set_additional_inset(additional_inset.horizontal());
+        self.vertical
+            .set_additional_inset(additional_inset.vertical());
+    }
+
+    /// The insets that determine the scrollable range, which is the sum of the content inset,
+    /// the safe area inset and the additional inset.
+    pub fn adjusted_content_inset(&self) -> EdgeInsets {
+        EdgeInsets::from_axes(
+            self.horizontal.adjusted_content_inset(),
+            self.vertical.adjusted_content_inset(),
+        )
+    }
+
+    pub fn set_deceleration_rate(&mut self, deceleration_rate: DecelerationRate) {
+        self.horizontal.set_deceleration_rate(deceleration_rate);
+        self.vertical.set_deceleration_rate(deceleration_rate);
+    }
+
+    /// Sets the response of the spring that bounces the content back at the edge.
+    pub fn set_bounce_response(&mut self, bounce_response: f32) {
+        self.horizontal.set_bounce_response(bounce_response);
+        self.vertical.set_bounce_response(bounce_response);
+    }
+
+    /// Sets the rubber band that resists dragging the content beyond the scrollable range
+    /// along both axes.
+    pub fn set_rubber_band<C: RubberBandCurve<f32> + Clone + 'static>(
+        &mut self,
+        rubber_band: RubberBand<f32, C>,
+    ) {
+        self.horizontal.set_rubber_band(rubber_band.clone());
+        self.vertical.set_rubber_band(rubber_band);
+    }
+
+    /// The directional lock that restricts a gesture to one axis, if any.
+    pub fn directional_lock(&self) -> Option<&DirectionalLock> {
+        self.directional_lock.as_ref()
+    }
+
+    /// Sets the directional lock, or removes it to let every gesture scroll along both axes.
+    pub fn set_directional_lock(&mut self, directional_lock: Option<DirectionalLock>) {
+        self.directional_lock = directional_lock;
+    }
+
+    /// The minimum point that the content can be scrolled to.
+    pub fn min_offset(&self) -> Point {
+        Point::new(self.horizontal.min_offset(), self.vertical.min_offset())
+    }
+
+    /// The maximum point that the content can be scrolled to.
+    pub fn max_offset(&self) -> Point {
+        Point::new(self.horizontal.max_offset(), self.vertical.max_offset())
+    }
+
+    /// The current content offset.
+    pub fn offset(&self) -> Point {
+        Point::new(self.horizontal.offset(), self.vertical.offset())
+    }
+
+    /// Moves the content to the given offset immediately and stops any running animation.
+    pub fn set_offset(&mut self, offset: Point) {
+        self.interrupt_completion();
+        self.horizontal.set_offset(offset.x);
+        self.vertical.set_offset(offset.y);
+    }
+
+    /// The velocity of the content at the last frame, in points per millisecond.
+    pub fn velocity(&self) -> Point {
+        Point::new(self.horizontal.velocity(), self.vertical.velocity())
+    }
+
+    /// The combined phase of both axes.
+    ///
+    /// When the axes are in different phases, the more active one is reported, in the order
+    /// of bouncing, dragging, decelerating, animating, tracking and idle.
+    pub fn phase(&self) -> ScrollPhase {
+        fn rank(phase: ScrollPhase) -> u8 {
+            match phase {
+                ScrollPhase::Idle => 0,
+                ScrollPhase::Tracking => 1,
+                ScrollPhase::Animating => 2,
+                ScrollPhase::Decelerating => 3,
+                ScrollPhase::Dragging => 4,
+                ScrollPhase::Bouncing => 5,
+            }
+        }
+        let horizontal = self.horizontal.phase();
+        let vertical = self.vertical.phase();
+        if rank(vertical) > rank(horizontal) {
+            vertical
+        } else {
+            horizontal
+        }
+    }
+
+    pub fn is_tracking(&self) -> bool {
+        self.horizontal.is_tracking() || self.vertical.is_tracking()
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.horizontal.is_dragging() || self.vertical.is_dragging()
+    }
+
+    pub fn is_decelerating(&self) -> bool {
+        self.horizontal.is_decelerating() || self.vertical.is_decelerating()
+    }
+
+    /// Tells the controller that a touch began at the given time and position.
+    pub fn touch_began(&mut self, time: f32, position: Point) {
+        self.interrupt_completion();
+        self.touch_began_position = position;
+        if let Some(lock) = self.directional_lock.as_mut() {
+            lock.reset();
+        }
+        self.horizontal.touch_began(time, position.x);
+        self.vertical.touch_began(time, position.y);
+    }
+
+    /// Tells the controller that the touch moved to the given position.
+    ///
+    /// With a directional lock, the content follows the touch along both axes until the
+    /// direction is decided, and then only moves along the axes that the gesture is locked to.
+    pub fn touch_moved(&mut self, time: f32, position: Point) {
+        let mut position = position;
+        if let Some(lock) = self.directional_lock.as_mut() {
+            let translation = Point::new(
+                position.x - self.touch_began_position.x,
+                position.y - self.touch_began_position.y,
+            );
+            let velocity = Point::new(
+                self.horizontal.release_velocity(time),
+                self.vertical.release_velocity(time),
+            );
+            lock.update(translation, velocity);
+            let translation = lock.filter_translation(translation);
+            position = Point::new(
+                self.touch_began_position.x + translation.x,
+                self.touch_began_position.y + translation.y,
+            );
+        }
+        self.horizontal.touch_moved(time, position.x);
+        self.vertical.touch_moved(time, position.y);
+    }
+
+    /// Tells the controller that the touch was lifted or cancelled at the given time.
+    pub fn touch_ended(&mut self, time: f32) {
+        let mut velocity = Point::new(
+            self.horizontal.release_velocity(time),
+            self.vertical.release_velocity(time),
+        );
+        if let Some(lock) = self.directional_lock.as_ref() {
+            velocity = lock.filter(velocity);
+        }
+        if VelocityTracker::approaching_halt(velocity.x, velocity.y) {
+            velocity = Point::ZERO;
+        }
+        self.horizontal.release(time, velocity.x);
+        self.vertical.release(time, velocity.y);
+    }
+
+    /// Scales the content size by `ratio` around `focal_point`, a point in the viewport,
+    /// so that the content under it stays in place while the content is zoomed.
+    ///
+    /// See [`ScrollController::scale_content`] for details.
+    pub fn scale_content(&mut self, ratio: f32, focal_point: Point) {
+        self.horizontal.scale_content(ratio, focal_point.x);
+        self.vertical.scale_content(ratio, focal_point.y);
+    }
+
+    /// Animates the content to the given offset with a spring on each axis.
+    ///
+    /// See [`ScrollController::animate_to`] for details.
+    pub fn animate_to(&mut self, now: f32, offset: Point) {
+        self.animate_to_with_completion(now, offset, |_| {});
+    }
+
+    /// Animates the content to the given offset like `animate_to`, and calls `completion`
+    /// once both axes have stopped.
+    ///
+    /// The completion receives `true` if the content came to rest on the target, or `false`
+    /// if the animation was interrupted by a touch, another animation or `set_offset`.
+    pub fn animate_to_with_completion<F>(&mut self, now: f32, offset: Point, completion: F)
+    where
+        F: FnOnce(bool) + 'static,
+    {
+        self.interrupt_completion();
+        self.horizontal.animate_to(now, offset.x);
+        self.vertical.animate_to(now, offset.y);
+        self.completion = Some(Completion(Box::new(completion)));
+    }
+
+    /// Advances the animation to the given time and returns the content offset at that moment.
+    pub fn tick(&mut self, now: f32) -> Point {
+        let offset = Point::new(self.horizontal.tick(now), self.vertical.tick(now));
+        let animating = self.horizontal.phase() == ScrollPhase::Animating
+            || self.vertical.phase() == ScrollPhase::Animating;
+        if !animating {
+            if let Some(Completion(completion)) = self.completion.take() {
+                completion(true);
+            }
+        }
+        offset
+    }
+}
+
+impl ScrollController2D {
+    fn interrupt_completion(&mut self) {
+        if let Some(Completion(completion)) = self.completion.take() {
+            completion(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use core::cell::Cell;
+
+    use crate::{
+        directional_lock::{DirectionalLock, LockDirection},
+        geometry::{Point, Size},
+        scroll_controller::ScrollPhase,
+    };
+
+    use super::ScrollController2D;
+
+    #[test]
+    fn test_per_axis_scrolling() {
+        let mut controller = ScrollController2D::new();
+        controller.set_content_size(Size::new(300.0, 2000.0));
+        controller.set_viewport_size(Size::new(400.0, 600.0));
+        controller.vertical_mut().set_bounces(false);
+
+        controller.touch_began(0.0, Point::new(200.0, 300.0));
+        for i in 1..=10 {
+            let t = i as f32 * 10.0;
+            controller.touch_moved(t, Point::new(200.0 - t, 300.0 + t));
+        }
+        // The content is narrower than the viewport, so it cannot move horizontally.
+        assert_eq!(controller.offset().x, 0.0);
+        // The vertical axis does not bounce, so it is clamped at the edge.
+        assert_eq!(controller.offset().y, 0.0);
+        controller.touch_ended(100.0);
+        assert_eq!(controller.phase(), ScrollPhase::Idle);
+        assert_eq!(controller.velocity(), Point::ZERO);
+
+        controller.horizontal_mut().set_always_bounce(true);
+        controller.touch_began(200.0, Point::new(200.0, 300.0));
+        for i in 1..=10 {
+            let t = i as f32 * 10.0;
+            controller.touch_moved(200.0 + t, Point::new(200.0 - t, 300.0 - t));
+        }
+        controller.touch_ended(300.0);
+        assert!(controller.offset().x > 0.0);
+        assert_eq!(controller.horizontal().phase(), ScrollPhase::Bouncing);
+        assert_eq!(controller.vertical().phase(), ScrollPhase::Decelerating);
+        assert_eq!(controller.phase(), ScrollPhase::Bouncing);
+    }
+
+    #[test]
+    fn test_phase_prefers_the_more_active_axis() {
+        let mut controller = ScrollController2D::new();
+        controller.set_content_size(Size::new(2000.0, 2000.0));
+        controller.set_viewport_size(Size::new(400.0, 600.0));
+        controller.horizontal_mut().touch_began(0.0, 200.0);
+        controller.vertical_mut().touch_began(0.0, 300.0);
+        controller.vertical_mut().touch_moved(10.0, 280.0);
+        assert_eq!(controller.horizontal().phase(), ScrollPhase::Tracking);
+        assert_eq!(controller.phase(), ScrollPhase::Dragging);
+
+        controller.vertical_mut().animate_to(20.0, 800.0);
+        assert_eq!(controller.phase(), ScrollPhase::Animating);
+    }
+
+    #[test]
+    fn test_directional_lock() {
+        let mut controller = ScrollController2D::new();
+        controller.set_content_size(Size::new(2000.0, 2000.0));
+        controller.set_viewport_size(Size::new(400.0, 600.0));
+        controller.set_offset(Point::new(500.0, 500.0));
+        controller.set_directional_lock(Some(DirectionalLock::new()));
+
+        // A mostly vertical gesture that drifts sideways.
+        controller.touch_began(0.0, Point::new(200.0, 300.0));
+        controller.touch_moved(10.0, Point::new(201.0, 295.0));
+        // The content follows the touch until the direction is decided.
+        assert_eq!(controller.offset(), Point::new(499.0, 505.0));
+        for i in 2..=10 {
+            let t = i as f32 * 10.0;
+            controller.touch_moved(t, Point::new(200.0 + t * 0.1, 300.0 - t));
+            // The content moves smoothly when the direction is decided.
+            assert!((controller.offset().y - (500.0 + t)).abs() < 1e-3);
+        }
+        assert_eq!(
+            controller.directional_lock().unwrap().direction(),
+            Some(LockDirection::Vertical)
+        );
+        // The sideways drift stops where it was when the direction was decided.
+        assert_eq!(controller.offset(), Point::new(498.0, 600.0));
+        assert_eq!(controller.phase(), ScrollPhase::Dragging);
+        controller.touch_ended(100.0);
+        assert_eq!(controller.horizontal().phase(), ScrollPhase::Idle);
+        assert_eq!(controller.velocity().x, 0.0);
+        assert_eq!(controller.vertical().phase(), ScrollPhase::Decelerating);
+
+        // A diagonal gesture scrolls along both axes.
+        controller.set_offset(Point::new(500.0, 500.0));
+        controller.touch_began(200.0, Point::new(200.0, 300.0));
+        for i in 1..=10 {
+            let t = i as f32 * 10.0;
+            controller.touch_moved(200.0 + t, Point::new(200.0 - t, 300.0 - t));
+        }
+        assert_eq!(controller.offset(), Point::new(600.0, 600.0));
+    }
+
+    #[test]
+    fn test_animate_to() {
+        let mut controller = ScrollController2D::new();
+        controller.set_content_size(Size::new(1000.0, 2000.0));
+        controller.set_viewport_size(Size::new(400.0, 600.0));
+
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        controller.animate_to_with_completion(0.0, Point::new(100.0, 800.0), move |finished| {
+            assert!(finished);
+            counter.set(counter.get() + 1);
+        });
+        let mut now = 0.0;
+        while controller.phase() != ScrollPhase::Idle {
+            now += 16.0;
+            controller.tick(now);
+        }
+        controller.tick(now + 16.0);
+        assert_eq!(controller.offset(), Point::new(100.0, 800.0));
+        assert_eq!(calls.get(), 1);
+    }
+}
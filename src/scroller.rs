@@ -12,16 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::ops::Deref;
+use core::ops::Deref;
 
-use crate::constants::VELOCITY_THRESHOLD;
+use crate::{constants::VELOCITY_THRESHOLD, math::Float};
+
+/// The range of deceleration rates that still feel like a natural fling.
+///
+/// It is used to clamp the deceleration rate solved by `Scroller::fling_to`.
+const MIN_DECELERATION_RATE: f64 = 0.98;
+const MAX_DECELERATION_RATE: f64 = 0.9995;
 
 /// Deceleration rates for the scroll animation.
 ///
 /// You can create a deceleration rate with the specified raw value.
 /// The raw value should be in the range of 0.0 to 1.0 (exclusive).
 #[derive(Copy, Clone, Debug, PartialEq)]
-pub struct DecelerationRate(pub f32);
+pub struct DecelerationRate<T = f32>(pub T);
 
 impl DecelerationRate {
     /// The default deceleration rate for a scroll animation.
@@ -30,14 +36,21 @@ impl DecelerationRate {
     pub const FAST: Self = Self(0.99);
 }
 
-impl Default for DecelerationRate {
+/// The default deceleration rate in any precision, which is the same as `NORMAL`.
+impl<T: Float> Default for DecelerationRate<T> {
     fn default() -> Self {
-        Self::NORMAL
+        Self(T::from_f64(0.998))
     }
 }
 
-impl Deref for DecelerationRate {
-    type Target = f32;
+impl From<DecelerationRate<f32>> for DecelerationRate<f64> {
+    fn from(deceleration_rate: DecelerationRate<f32>) -> Self {
+        Self(deceleration_rate.0.into())
+    }
+}
+
+impl<T> Deref for DecelerationRate<T> {
+    type Target = T;
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -45,54 +58,152 @@ impl Deref for DecelerationRate {
 }
 
 #[derive(Debug)]
-pub struct Scroller {
-    deceleration_rate: DecelerationRate,
-    initial_velocity: f32,
+pub struct Scroller<T = f32> {
+    deceleration_rate: DecelerationRate<T>,
+    initial_velocity: T,
 }
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
-pub struct ScrollerValue {
-    pub offset: f32,
-    pub velocity: f32,
+pub struct ScrollerValue<T = f32> {
+    pub offset: T,
+    pub velocity: T,
 }
 
-impl Scroller {
-    pub fn new(deceleration_rate: DecelerationRate) -> Self {
+impl<T: Float> Scroller<T> {
+    pub fn new(deceleration_rate: DecelerationRate<T>) -> Self {
         Self {
             deceleration_rate,
-            initial_velocity: 0.0,
+            initial_velocity: T::zero(),
         }
     }
 
-    pub fn set_deceleration_rate(&mut self, deceleration_rate: DecelerationRate) {
+    pub fn deceleration_rate(&self) -> DecelerationRate<T> {
+        self.deceleration_rate
+    }
+
+    pub fn set_deceleration_rate(&mut self, deceleration_rate: DecelerationRate<T>) {
         self.deceleration_rate = deceleration_rate;
     }
 
-    pub fn fling(&mut self, velocity: f32) {
+    pub fn fling(&mut self, velocity: T) {
         self.initial_velocity = velocity;
     }
 
-    pub fn value(&mut self, time: f32) -> Option<ScrollerValue> {
+    /// Starts a fling with the given velocity that comes to rest at `target`,
+    /// which is relative to where the fling starts.
+    ///
+    /// Instead of changing the velocity, the deceleration rate is adjusted so that the fling
+    /// keeps the feel of the decay animation. The solved rate is clamped to a natural range,
+    /// and it replaces the rate of the scroller.
+    ///
+    /// Returns `true` if the fling lands exactly on the target. Otherwise it comes to rest at
+    /// [`Scroller::final_offset`], as close to the target as the clamped rate allows.
+    pub fn fling_to(&mut self, velocity: T, target: T) -> bool {
+        let threshold = T::from_f64(VELOCITY_THRESHOLD);
+        let min_rate = T::from_f64(MIN_DECELERATION_RATE);
+        let max_rate = T::from_f64(MAX_DECELERATION_RATE);
+        self.initial_velocity = velocity;
+        if velocity.abs() < threshold {
+            return target == T::zero();
+        }
+        if target == T::zero() || target.is_sign_negative() != velocity.is_sign_negative() {
+            // The fling always travels in the direction of its velocity.
+            self.deceleration_rate = DecelerationRate(min_rate);
+            return false;
+        }
+        // The fling stops when the velocity decays to the threshold, so the travelled distance
+        // is `-(v - sign(v) * threshold) / ln(rate)`.
+        let rate = (-(velocity - threshold.copysign(velocity)) / target).exp();
+        let clamped = rate.max(min_rate).min(max_rate);
+        self.deceleration_rate = DecelerationRate(clamped);
+        rate == clamped
+    }
+
+    pub fn value(&mut self, time: T) -> Option<ScrollerValue<T>> {
         let rate = *self.deceleration_rate;
         let coefficient = rate.powf(time);
         let velocity = self.initial_velocity * coefficient;
-        let offset = self.initial_velocity * (1.0 / rate.ln()) * (coefficient - 1.0);
+        let offset = self.initial_velocity * (T::one() / rate.ln()) * (coefficient - T::one());
 
-        if velocity.abs() < VELOCITY_THRESHOLD {
+        if velocity.abs() < T::from_f64(VELOCITY_THRESHOLD) {
             return None;
         }
 
         Some(ScrollerValue { offset, velocity })
     }
 
+    /// Returns the offset at which the fling comes to rest, relative to where it started.
+    pub fn final_offset(&self) -> T {
+        let threshold = T::from_f64(VELOCITY_THRESHOLD);
+        let velocity = self.initial_velocity;
+        if velocity.abs() < threshold {
+            return T::zero();
+        }
+        // At the end of the animation, the decayed velocity `v0 * rate^t` equals the threshold.
+        let coefficient = threshold / velocity.abs();
+        velocity * (T::one() / self.deceleration_rate.ln()) * (coefficient - T::one())
+    }
+
+    /// Returns the time in milliseconds that the fling takes to come to rest.
+    pub fn duration(&self) -> T {
+        let threshold = T::from_f64(VELOCITY_THRESHOLD);
+        let velocity = self.initial_velocity.abs();
+        if velocity < threshold {
+            return T::zero();
+        }
+        (threshold / velocity).ln() / self.deceleration_rate.ln()
+    }
+
     pub fn reset(&mut self) {
-        self.initial_velocity = 0.0;
+        self.initial_velocity = T::zero();
     }
 }
 
-impl Default for Scroller {
+impl<T: Float> Default for Scroller<T> {
     fn default() -> Self {
-        Self::new(DecelerationRate::NORMAL)
+        Self::new(DecelerationRate::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DecelerationRate, Scroller};
+
+    #[test]
+    fn test_final_offset_and_duration() {
+        let mut scroller = Scroller::new(DecelerationRate::NORMAL);
+        scroller.fling(-3.0);
+        let duration = scroller.duration();
+        let final_offset = scroller.final_offset();
+
+        let value = scroller.value(duration - 1.0).unwrap();
+        assert!((value.offset - final_offset).abs() < 0.1);
+        assert!(scroller.value(duration + 1.0).is_none());
+        assert!(final_offset < 0.0);
+    }
+
+    #[test]
+    fn test_fling_to() {
+        let mut scroller = Scroller::<f32>::default();
+        assert!(scroller.fling_to(2.0, 1200.0));
+        assert!((scroller.final_offset() - 1200.0).abs() < 0.1);
+
+        // The target is too close to be reached with a natural deceleration rate.
+        assert!(!scroller.fling_to(2.0, 10.0));
+        assert!(scroller.final_offset() > 10.0);
+        // The target is in the opposite direction of the velocity.
+        assert!(!scroller.fling_to(-2.0, 500.0));
+    }
+
+    #[test]
+    fn test_f64_matches_f32() {
+        let mut scroller = Scroller::<f64>::new(DecelerationRate::NORMAL.into());
+        scroller.fling(-3.0);
+        let mut reference = Scroller::new(DecelerationRate::NORMAL);
+        reference.fling(-3_f32);
+
+        assert!((scroller.final_offset() - reference.final_offset() as f64).abs() < 0.1);
+        assert!((scroller.duration() - reference.duration() as f64).abs() < 1.0);
     }
 }
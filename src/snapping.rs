@@ -0,0 +1,204 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+
+use crate::{
+    scroller::{DecelerationRate, Scroller},
+    spring_back::{valid_response, DEFAULT_RESPONSE},
+};
+
+/// The alignment of an item relative to the viewport when the content snaps to it.
+///
+/// It behaves like the `scroll-snap-align` property in CSS.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SnapAlignment {
+    /// The start edge of the item is aligned with the start edge of the viewport.
+    #[default]
+    Start,
+    /// The center of the item is aligned with the center of the viewport.
+    Center,
+    /// The end edge of the item is aligned with the end edge of the viewport.
+    End,
+}
+
+impl SnapAlignment {
+    /// Returns the content offset at which an item is aligned with the viewport.
+    pub fn offset(&self, item_start: f32, item_length: f32, viewport_length: f32) -> f32 {
+        match self {
+            Self::Start => item_start,
+            Self::Center => item_start + (item_length - viewport_length) / 2.0,
+            Self::End => item_start + item_length - viewport_length,
+        }
+    }
+}
+
+/// The way the content moves to the chosen snap point after the touch is released.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SnapTrajectory {
+    /// The content keeps decaying with the release velocity, using a deceleration rate
+    /// that makes it come to rest exactly on the target.
+    Decay {
+        target: f32,
+        deceleration_rate: DecelerationRate,
+    },
+    /// The content moves to the target with a spring, starting with the release velocity.
+    Spring { target: f32 },
+}
+
+impl SnapTrajectory {
+    /// The offset at which the content comes to rest.
+    pub fn target(&self) -> f32 {
+        match *self {
+            Self::Decay { target, .. } | Self::Spring { target } => target,
+        }
+    }
+}
+
+/// A set of offsets that the content is allowed to come to rest on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapPoints {
+    offsets: Vec<f32>,
+    response: f32,
+}
+
+impl SnapPoints {
+    /// Creates snap points with the given content offsets.
+    ///
+    /// The offsets are sorted if they are not already.
+    pub fn new(mut offsets: Vec<f32>) -> Self {
+        offsets.sort_by(f32::total_cmp);
+        Self {
+            offsets,
+            response: DEFAULT_RESPONSE,
+        }
+    }
+
+    /// Creates snap points that align each item with the viewport.
+    ///
+    /// Each item is described by its start position and its length along the axis.
+    pub fn from_items<I>(items: I, alignment: SnapAlignment, viewport_length: f32) -> Self
+    where
+        I: IntoIterator<Item = (f32, f32)>,
+    {
+        Self::new(
+            items
+                .into_iter()
+                .map(|(start, length)| alignment.offset(start, length, viewport_length))
+                .collect(),
+        )
+    }
+
+    pub fn offsets(&self) -> &[f32] {
+        &self.offsets
+    }
+
+    /// The response of the spring used when the content cannot decay onto the snap point.
+    pub fn response(&self) -> f32 {
+        self.response
+    }
+
+    pub fn set_response(&mut self, response: f32) {
+        self.response = valid_response(response);
+    }
+
+    /// Returns the snap point nearest to the given offset.
+    pub fn nearest(&self, offset: f32) -> Option<f32> {
+        let index = self.offsets.partition_point(|&point| point < offset);
+        let after = self.offsets.get(index).copied();
+        let before = index
+            .checked_sub(1)
+            .and_then(|index| self.offsets.get(index))
+            .copied();
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                if offset - before <= after - offset {
+                    Some(before)
+                } else {
+                    Some(after)
+                }
+            }
+            (before, after) => before.or(after),
+        }
+    }
+
+    /// Projects a fling from `offset` with `velocity` to its natural resting point,
+    /// and returns the trajectory that lands on the snap point nearest to it.
+    ///
+    /// When landing on the snap point requires an unnatural deceleration rate,
+    /// a spring is used instead.
+    pub fn trajectory(
+        &self,
+        offset: f32,
+        velocity: f32,
+        deceleration_rate: DecelerationRate,
+    ) -> Option<SnapTrajectory> {
+        let mut scroller = Scroller::new(deceleration_rate);
+        scroller.fling(velocity);
+        let target = self.nearest(offset + scroller.final_offset())?;
+        Some(
+            if target != offset && scroller.fling_to(velocity, target - offset) {
+                SnapTrajectory::Decay {
+                    target,
+                    deceleration_rate: scroller.deceleration_rate(),
+                }
+            } else {
+                SnapTrajectory::Spring { target }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::scroller::{DecelerationRate, Scroller};
+
+    use super::{SnapAlignment, SnapPoints, SnapTrajectory};
+
+    #[test]
+    fn test_nearest() {
+        let snap_points = SnapPoints::from_items(
+            [(0.0, 200.0), (200.0, 200.0), (400.0, 200.0)],
+            SnapAlignment::Center,
+            400.0,
+        );
+        assert_eq!(snap_points.offsets(), &[-100.0, 100.0, 300.0]);
+        assert_eq!(snap_points.nearest(-500.0), Some(-100.0));
+        assert_eq!(snap_points.nearest(180.0), Some(100.0));
+        assert_eq!(snap_points.nearest(1000.0), Some(300.0));
+        assert_eq!(SnapPoints::new(vec![]).nearest(0.0), None);
+    }
+
+    #[test]
+    fn test_decay_lands_on_snap_point() {
+        let snap_points = SnapPoints::new(vec![0.0, 500.0, 1000.0, 1500.0]);
+        let trajectory = snap_points
+            .trajectory(0.0, 2.0, DecelerationRate::NORMAL)
+            .unwrap();
+        let SnapTrajectory::Decay {
+            target,
+            deceleration_rate,
+        } = trajectory
+        else {
+            panic!("expected a decay trajectory, got {:?}", trajectory);
+        };
+        assert_eq!(target, 1000.0);
+
+        let mut scroller = Scroller::new(deceleration_rate);
+        scroller.fling(2.0);
+        assert!((scroller.final_offset() - target).abs() < 0.1);
+    }
+}
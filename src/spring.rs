@@ -0,0 +1,281 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    constants::{VALUE_THRESHOLD, VELOCITY_THRESHOLD},
+    math::Float,
+    spring_back::{valid_response, DEFAULT_RESPONSE_F64},
+};
+
+/// Damping ratios closer to 1 than this are solved as a critically damped spring,
+/// since the underdamped and overdamped solutions are numerically unstable around it.
+const CRITICAL_DAMPING_TOLERANCE: f64 = 1e-3;
+
+/// The smallest damping ratio that a spring can have. An undamped spring would oscillate
+/// forever and never come to rest.
+const MIN_DAMPING_RATIO: f64 = 0.05;
+
+/// A damped harmonic oscillator that moves a value toward an arbitrary target.
+///
+/// Unlike `SpringBack`, which is always critically damped, the spring can be underdamped
+/// (it oscillates around the target), critically damped or overdamped.
+#[derive(Debug, Clone)]
+pub struct Spring<T = f32> {
+    // The undamped angular frequency in radians per second.
+    omega: T,
+    damping_ratio: T,
+
+    target: T,
+    // The displacement from the target when the animation started.
+    initial_displacement: T,
+    // The velocity when the animation started, in points per second.
+    initial_velocity: T,
+    // The time in milliseconds at which the current solution starts.
+    time_origin: T,
+}
+
+impl<T: Float> Spring<T> {
+    /// Creates a spring with the given response and damping ratio, like SwiftUI's `spring`.
+    ///
+    /// The response is the duration of one period of the undamped spring, in seconds.
+    /// A damping ratio less than 1 makes the spring bounce, 1 is critically damped,
+    /// and greater than 1 makes the spring approach the target slowly without overshooting.
+    /// The damping ratio is clamped to a small positive minimum, so that the spring always
+    /// comes to rest.
+    pub fn new(response: T, damping_ratio: T) -> Self {
+        let response = valid_response(response);
+        Self {
+            omega: T::TAU() / response,
+            damping_ratio: damping_ratio.max(T::from_f64(MIN_DAMPING_RATIO)),
+            target: T::zero(),
+            initial_displacement: T::zero(),
+            initial_velocity: T::zero(),
+            time_origin: T::zero(),
+        }
+    }
+
+    /// Creates a spring from the physical properties of a mass attached to a spring.
+    ///
+    /// A mass or stiffness that is not positive has no natural frequency, so the default
+    /// spring is returned instead.
+    pub fn with_mass_stiffness_damping(mass: T, stiffness: T, damping: T) -> Self {
+        if !(mass > T::zero() && stiffness > T::zero()) {
+            return Self::default();
+        }
+        let omega = (stiffness / mass).sqrt();
+        let damping_ratio = damping / (T::from_f64(2.0) * (stiffness * mass).sqrt());
+        Self::new(T::TAU() / omega, damping_ratio)
+    }
+
+    /// The duration of one period of the undamped spring, in seconds.
+    pub fn response(&self) -> T {
+        T::TAU() / self.omega
+    }
+
+    pub fn damping_ratio(&self) -> T {
+        self.damping_ratio
+    }
+
+    /// The value that the spring moves toward.
+    pub fn target(&self) -> T {
+        self.target
+    }
+
+    /// Starts the animation from `value` with `velocity` toward `target`.
+    ///
+    /// The unit of velocity is points per millisecond.
+    pub fn start(&mut self, value: T, target: T, velocity: T) {
+        self.target = target;
+        self.initial_displacement = value - target;
+        // The formula needs to be calculated in units of points per second.
+        self.initial_velocity = velocity * T::from_f64(1e3);
+        self.time_origin = T::zero();
+    }
+
+    /// Changes the target at the given time in milliseconds since the animation started,
+    /// without changing the value and velocity at that moment.
+    ///
+    /// The time keeps being measured from when the animation started.
+    pub fn retarget(&mut self, time: T, target: T) {
+        let (displacement, velocity) = self.solve(time);
+        self.initial_displacement = self.target + displacement - target;
+        self.initial_velocity = velocity;
+        self.target = target;
+        self.time_origin = time;
+    }
+
+    /// Returns the value at the given time in milliseconds since the animation started,
+    /// or `None` if the spring has come to rest.
+    pub fn value(&self, time: T) -> Option<T> {
+        let (displacement, velocity) = self.solve(time);
+        // The velocity threshold is in units of points per millisecond.
+        if displacement.abs() < T::from_f64(VALUE_THRESHOLD)
+            && velocity.abs() / T::from_f64(1e3) < T::from_f64(VELOCITY_THRESHOLD)
+        {
+            None
+        } else {
+            Some(self.target + displacement)
+        }
+    }
+
+    /// Returns the velocity at the given time in milliseconds since the animation started.
+    ///
+    /// The unit of velocity is points per millisecond.
+    pub fn velocity(&self, time: T) -> T {
+        self.solve(time).1 / T::from_f64(1e3)
+    }
+
+    pub fn reset(&mut self) {
+        self.target = T::zero();
+        self.initial_displacement = T::zero();
+        self.initial_velocity = T::zero();
+        self.time_origin = T::zero();
+    }
+}
+
+impl<T: Float> Spring<T> {
+    /// Calculates the displacement from the target and the velocity at a given time
+    /// in milliseconds since the animation started.
+    ///
+    /// The unit of velocity is points per second.
+    fn solve(&self, time: T) -> (T, T) {
+        // Convert time from milliseconds to seconds.
+        let time = (time - self.time_origin) / T::from_f64(1e3);
+        let one = T::one();
+        let omega = self.omega;
+        let zeta = self.damping_ratio;
+        let x0 = self.initial_displacement;
+        let v0 = self.initial_velocity;
+
+        if (zeta - one).abs() < T::from_f64(CRITICAL_DAMPING_TOLERANCE) {
+            let decay = (-omega * time).exp();
+            let c = v0 + omega * x0;
+            let displacement = (x0 + c * time) * decay;
+            let velocity = (v0 - omega * c * time) * decay;
+            (displacement, velocity)
+        } else if zeta < one {
+            let damped_omega = omega * (one - zeta * zeta).sqrt();
+            let decay = (-zeta * omega * time).exp();
+            let (sin, cos) = (damped_omega * time).sin_cos();
+            let a = x0;
+            let b = (v0 + zeta * omega * x0) / damped_omega;
+            let displacement = decay * (a * cos + b * sin);
+            let velocity =
+                decay * (-zeta * omega * (a * cos + b * sin) + damped_omega * (b * cos - a * sin));
+            (displacement, velocity)
+        } else {
+            let s = omega * (zeta * zeta - one).sqrt();
+            let r1 = -zeta * omega + s;
+            let r2 = -zeta * omega - s;
+            let c2 = (v0 - r1 * x0) / (r2 - r1);
+            let c1 = x0 - c2;
+            let (e1, e2) = ((r1 * time).exp(), (r2 * time).exp());
+            (c1 * e1 + c2 * e2, c1 * r1 * e1 + c2 * r2 * e2)
+        }
+    }
+}
+
+impl<T: Float> Default for Spring<T> {
+    fn default() -> Self {
+        Self::new(T::from_f64(DEFAULT_RESPONSE_F64), T::one())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spring_back::SpringBack;
+
+    use super::Spring;
+
+    fn settle(spring: &Spring) -> (f32, f32) {
+        let mut time = 0.0;
+        let mut extreme = spring.value(0.0).unwrap();
+        while let Some(value) = spring.value(time) {
+            extreme = extreme.max(value);
+            time += 1.0;
+        }
+        (time, extreme)
+    }
+
+    #[test]
+    fn test_damping_ratios() {
+        let mut underdamped = Spring::new(0.5, 0.3);
+        underdamped.start(0.0, 100.0, 0.0);
+        let (_, extreme) = settle(&underdamped);
+        assert!(extreme > 100.0);
+
+        let mut overdamped = Spring::with_mass_stiffness_damping(1.0, 100.0, 40.0);
+        assert!(overdamped.damping_ratio() > 1.0);
+        overdamped.start(0.0, 100.0, 0.0);
+        let (duration, extreme) = settle(&overdamped);
+        assert!(extreme < 100.0);
+        assert!(duration > 0.0);
+
+        // The velocity is the derivative of the value.
+        let (value, next) = (overdamped.value(100.0), overdamped.value(101.0));
+        let velocity = overdamped.velocity(100.5);
+        assert!((next.unwrap() - value.unwrap() - velocity).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_undamped_spring_comes_to_rest() {
+        let mut spring = Spring::<f32>::new(0.5, 0.0);
+        assert!(spring.damping_ratio() > 0.0);
+        spring.start(0.0, 100.0, 0.0);
+        let mut time = 0.0;
+        while spring.value(time).is_some() {
+            time += 16.0;
+            assert!(time < 60_000.0);
+        }
+    }
+
+    #[test]
+    fn test_invalid_mass_or_stiffness_falls_back_to_default() {
+        for (mass, stiffness) in [(0.0, 100.0), (-1.0, 100.0), (1.0, 0.0), (1.0, -5.0)] {
+            let mut spring = Spring::<f32>::with_mass_stiffness_damping(mass, stiffness, 10.0);
+            assert_eq!(spring.response(), Spring::<f32>::default().response());
+            spring.start(0.0, 100.0, 0.0);
+            assert!(spring.value(100.0).unwrap().is_finite());
+        }
+    }
+
+    #[test]
+    fn test_critically_damped_matches_spring_back() {
+        let mut spring = Spring::<f32>::new(0.4, 1.0);
+        spring.start(250.0, 200.0, 1.5);
+        let mut spring_back = SpringBack::new();
+        spring_back.absorb_with_response(1.5, 50.0, 0.4);
+        for time in [0.0, 16.0, 100.0, 300.0] {
+            let expected = spring_back.value(time).unwrap().offset + 200.0;
+            assert!((spring.value(time).unwrap() - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_default_response_is_exact_in_f64() {
+        let spring = Spring::<f64>::default();
+        assert!((spring.response() - 0.575).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_retarget() {
+        let mut spring = Spring::<f32>::new(0.5, 0.8);
+        spring.start(0.0, 100.0, 0.5);
+        let (value, velocity) = (spring.value(80.0).unwrap(), spring.velocity(80.0));
+        spring.retarget(80.0, -50.0);
+        assert!((spring.value(80.0).unwrap() - value).abs() < 1e-3);
+        assert!((spring.velocity(80.0) - velocity).abs() < 1e-4);
+        assert_eq!(spring.target(), -50.0);
+    }
+}
@@ -12,60 +12,150 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::f32::consts::PI;
+use crate::{
+    constants::{VALUE_THRESHOLD, VELOCITY_THRESHOLD},
+    math::Float,
+};
 
-use crate::constants::{VALUE_THRESHOLD, VELOCITY_THRESHOLD};
+/// The default response in seconds, in `f64` so that generic simulations get the exact value
+/// instead of a widened `f32`.
+pub(crate) const DEFAULT_RESPONSE_F64: f64 = 0.575;
+pub(crate) const DEFAULT_RESPONSE: f32 = DEFAULT_RESPONSE_F64 as f32;
 
-const DEFAULT_RESPONSE: f32 = 0.575_f32;
+/// Returns `response`, or the default response if it is not positive.
+pub(crate) fn valid_response<T: Float>(response: T) -> T {
+    if response > T::zero() {
+        response
+    } else {
+        T::from_f64(DEFAULT_RESPONSE_F64)
+    }
+}
 
+/// The state of a `SpringBack` animation at a given time.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct SpringBackValue<T = f32> {
+    pub offset: T,
+    /// The velocity in points per millisecond.
+    pub velocity: T,
+}
+
+/// A critically damped spring that brings the content back to rest, like the bounce at the edge.
+///
+/// The stiffness of the spring is given as its response, defined as an approximate duration
+/// in seconds. The controllers fall back to the default response of 0.575 seconds when they
+/// are given a response that is not positive.
 #[derive(Debug, Default)]
-pub struct SpringBack {
-    lambda: f32,
-    c1: f32,
-    c2: f32,
+pub struct SpringBack<T = f32> {
+    lambda: T,
+    c1: T,
+    c2: T,
+    // The rest position, which is 0 unless the spring has been retargeted.
+    target: T,
+    // The time in milliseconds at which the current solution starts.
+    time_origin: T,
 }
 
-impl SpringBack {
-    pub fn new() -> SpringBack {
+impl<T: Float> SpringBack<T> {
+    pub fn new() -> Self {
         Self::default()
     }
 
-    pub fn absorb(&mut self, velocity: f32, distance: f32) {
-        self.absorb_with_response(velocity, distance, DEFAULT_RESPONSE)
+    pub fn absorb(&mut self, velocity: T, distance: T) {
+        self.absorb_with_response(velocity, distance, T::from_f64(DEFAULT_RESPONSE_F64))
     }
 
-    pub fn absorb_with_response(&mut self, velocity: f32, distance: f32, response: f32) {
-        self.lambda = 2_f32 * PI / response;
+    pub fn absorb_with_response(&mut self, velocity: T, distance: T, response: T) {
+        self.lambda = T::TAU() / response;
         self.c1 = distance;
         // The formula needs to be calculated in units of points per second.
-        self.c2 = velocity * 1e3 + self.lambda * distance;
+        self.c2 = velocity * T::from_f64(1e3) + self.lambda * distance;
+        self.target = T::zero();
+        self.time_origin = T::zero();
     }
 
-    pub fn value(&self, mut time: f32) -> Option<f32> {
+    /// Returns the offset and velocity at the given time in milliseconds,
+    /// or `None` if the animation has come to rest.
+    pub fn value(&self, time: T) -> Option<SpringBackValue<T>> {
         // Convert time from milliseconds to seconds.
-        time /= 1e3;
+        let time = (time - self.time_origin) / T::from_f64(1e3);
         let offset = (self.c1 + self.c2 * time) * (-self.lambda * time).exp();
 
-        let velocity = self.velocity_at(time);
         // The velocity threshold is in units of points per millisecond.
         // We need to convert velocity to match the unit.
-        if offset.abs() < VALUE_THRESHOLD && velocity.abs() / 1e3 < VELOCITY_THRESHOLD {
+        let velocity = self.velocity_at(time) / T::from_f64(1e3);
+        if offset.abs() < T::from_f64(VALUE_THRESHOLD)
+            && velocity.abs() < T::from_f64(VELOCITY_THRESHOLD)
+        {
             None
         } else {
-            Some(offset)
+            Some(SpringBackValue {
+                offset: self.target + offset,
+                velocity,
+            })
         }
     }
 
+    /// Returns the velocity at the given time in milliseconds.
+    ///
+    /// The unit of velocity is points per millisecond, which is the same as the unit
+    /// accepted by `absorb` and `Scroller::fling`.
+    pub fn velocity(&self, time: T) -> T {
+        let milliseconds = T::from_f64(1e3);
+        self.velocity_at((time - self.time_origin) / milliseconds) / milliseconds
+    }
+
+    /// Moves the rest position of the spring to `target` at the given time, without changing
+    /// the position and velocity at that moment.
+    ///
+    /// The target is in the same coordinate space as the values returned by the spring,
+    /// where the rest position is 0 before any retargeting. The time keeps being measured
+    /// from when the animation started, and the values move toward the new target afterwards.
+    pub fn retarget(&mut self, time: T, target: T) {
+        let elapsed = (time - self.time_origin) / T::from_f64(1e3);
+        let offset = self.target + (self.c1 + self.c2 * elapsed) * (-self.lambda * elapsed).exp();
+        let velocity = self.velocity_at(elapsed);
+
+        self.target = target;
+        self.time_origin = time;
+        self.c1 = offset - target;
+        self.c2 = velocity + self.lambda * self.c1;
+    }
+
     pub fn reset(&mut self) {
         *self = Self::default();
     }
 }
 
-impl SpringBack {
+impl<T: Float> SpringBack<T> {
     /// Calculate the velocity at a given time.
     ///
     /// The unit of velocity is points per second.
-    fn velocity_at(&self, time: f32) -> f32 {
+    fn velocity_at(&self, time: T) -> T {
         (self.c2 - self.lambda * (self.c1 + self.c2 * time)) * (-self.lambda * time).exp()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SpringBack;
+
+    #[test]
+    fn test_retarget_is_continuous() {
+        let mut spring_back = SpringBack::<f32>::new();
+        spring_back.absorb_with_response(2.0, 300.0, 0.5);
+        let before = spring_back.value(120.0).unwrap();
+        let velocity = spring_back.velocity(120.0);
+        assert_eq!(before.velocity, velocity);
+
+        spring_back.retarget(120.0, -100.0);
+        assert!((spring_back.value(120.0).unwrap().offset - before.offset).abs() < 1e-3);
+        assert!((spring_back.velocity(120.0) - velocity).abs() < 1e-4);
+
+        let mut time = 120.0;
+        while spring_back.value(time).is_some() {
+            time += 16.0;
+        }
+        assert!((spring_back.value(time - 16.0).unwrap().offset + 100.0).abs() < 1.0);
+    }
+}
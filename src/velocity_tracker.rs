@@ -13,274 +13,549 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloc::vec::Vec;
-use core::{
-    cell::RefCell,
-    ops::{Index, IndexMut},
-};
-use std::ops::Deref;
-
-extern crate alloc;
-
-#[derive(Debug, Clone, Copy, Default)]
-struct DataPoint {
-    pub time: f32,
-    pub value: f32,
+use crate::{geometry::Point, math::Float};
+
+#[derive(Debug, Clone, Copy)]
+struct DataPoint<T, const N: usize> {
+    pub time: T,
+    pub values: [T; N],
 }
 
+/// The maximum number of samples that a velocity tracker can keep.
+const MAX_HISTORY_SIZE: usize = 64;
+
 const HISTORY_SIZE: usize = 20;
 const HORIZON_MILLISECONDS: f32 = 100_f32;
 const ASSUME_POINTER_MOVE_STOPPED_MILLISECONDS: f32 = 40_f32;
 
-#[derive(Debug)]
-struct Cache {
-    reusable_values: Vector,
-    reusable_time: Vector,
+/// The number of most recent samples used by the recurrence relation.
+const RECURRENCE_SAMPLE_SIZE: usize = 4;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// A weighted average of the recent velocities that imitates `UIScrollView`.
+    ///
+    /// Like `UIScrollView`, the velocity is zero if the pointer rests before it is lifted.
+    #[default]
+    Recurrence = 0,
+    /// Least squares fit of a quadratic polynomial.
+    Lsq2 = 1,
+    /// Least squares fit of a linear polynomial.
+    Lsq1 = 2,
+    /// Least squares fit of a cubic polynomial.
+    Lsq3 = 3,
+    /// Weighted least squares fit of a quadratic polynomial, weighing each sample by the
+    /// time elapsed until the next one, so samples that cover a shorter time span count less.
+    Wlsq2Delta = 4,
+    /// Weighted least squares fit of a quadratic polynomial, weighing very recent and very old
+    /// samples less.
+    Wlsq2Central = 5,
+    /// Weighted least squares fit of a quadratic polynomial, weighing older samples less.
+    Wlsq2Recent = 6,
+    /// Physical model of the kinetic energy imparted by each movement, which is the default
+    /// strategy of recent versions of Android.
+    Impulse = 7,
 }
 
-impl Cache {
-    fn new() -> Self {
-        Self {
-            reusable_values: Vector::with_capacity(HISTORY_SIZE),
-            reusable_time: Vector::with_capacity(HISTORY_SIZE),
-        }
-    }
+/// The weighting that a least squares strategy applies to the samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Weighting {
+    None,
+    Delta,
+    Central,
+    Recent,
 }
 
-impl Default for Cache {
-    fn default() -> Self {
-        Self::new()
+impl Weighting {
+    /// Returns the weight of the sample at `index`, in a list of sample times that are
+    /// relative to the newest sample and ordered from newest to oldest.
+    fn weight<T: Float>(&self, time: &[T], index: usize) -> T {
+        let age = -time[index];
+        let c = T::from_f64;
+        match self {
+            Weighting::None => c(1.0),
+            Weighting::Delta => {
+                // delta  0ms: 0.5
+                // delta 10ms: 1.0
+                if index == 0 {
+                    return c(1.0);
+                }
+                let delta = time[index - 1] - time[index];
+                if delta < c(0.0) {
+                    c(0.5)
+                } else if delta < c(10.0) {
+                    c(0.5) + delta * c(0.05)
+                } else {
+                    c(1.0)
+                }
+            }
+            Weighting::Central => {
+                // age  0ms: 0.5
+                // age 10ms: 1.0
+                // age 50ms: 1.0
+                // age 60ms: 0.5
+                if age < c(0.0) {
+                    c(0.5)
+                } else if age < c(10.0) {
+                    c(0.5) + age * c(0.05)
+                } else if age < c(50.0) {
+                    c(1.0)
+                } else if age < c(60.0) {
+                    c(0.5) + (c(60.0) - age) * c(0.05)
+                } else {
+                    c(0.5)
+                }
+            }
+            Weighting::Recent => {
+                // age   0ms: 1.0
+                // age  50ms: 1.0
+                // age 100ms: 0.5
+                if age < c(50.0) {
+                    c(1.0)
+                } else if age < c(100.0) {
+                    c(0.5) + (c(100.0) - age) * c(0.01)
+                } else {
+                    c(0.5)
+                }
+            }
+        }
     }
 }
 
-#[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Strategy {
-    Recurrence = 0,
-    Lsq2 = 1,
-}
+impl Strategy {
+    fn min_sample_size(&self) -> usize {
+        match self {
+            Strategy::Recurrence | Strategy::Lsq1 | Strategy::Impulse => 2,
+            Strategy::Lsq2
+            | Strategy::Wlsq2Delta
+            | Strategy::Wlsq2Central
+            | Strategy::Wlsq2Recent => 3,
+            Strategy::Lsq3 => 4,
+        }
+    }
 
-impl Default for Strategy {
-    fn default() -> Self {
-        Self::Recurrence
+    /// Estimates the velocity from the samples, ordered from newest to oldest.
+    ///
+    /// The times are relative to the newest sample.
+    fn estimate<T: Float>(&self, time: &[T], values: &[T]) -> T {
+        let sample_count = time.len();
+        if sample_count < self.min_sample_size() {
+            return T::zero();
+        }
+        let (degree, weighting) = match self {
+            Strategy::Recurrence => {
+                // The recurrence relation runs from the oldest sample to the newest one.
+                let count = sample_count.min(RECURRENCE_SAMPLE_SIZE);
+                let mut recent_time = [T::zero(); RECURRENCE_SAMPLE_SIZE];
+                let mut recent_values = [T::zero(); RECURRENCE_SAMPLE_SIZE];
+                for i in 0..count {
+                    recent_time[i] = time[count - 1 - i];
+                    recent_values[i] = values[count - 1 - i];
+                }
+                return calculate_recurrence_relation_velocity(
+                    &recent_time[..count],
+                    &recent_values[..count],
+                )
+                .unwrap_or_default();
+            }
+            Strategy::Impulse => return calculate_impulse_velocity(time, values),
+            Strategy::Lsq1 => (1, Weighting::None),
+            Strategy::Lsq2 => (2, Weighting::None),
+            Strategy::Lsq3 => (3, Weighting::None),
+            Strategy::Wlsq2Delta => (2, Weighting::Delta),
+            Strategy::Wlsq2Central => (2, Weighting::Central),
+            Strategy::Wlsq2Recent => (2, Weighting::Recent),
+        };
+        let mut weights = [T::zero(); MAX_HISTORY_SIZE];
+        for (index, weight) in weights.iter_mut().take(sample_count).enumerate() {
+            *weight = weighting.weight(time, index);
+        }
+        // The 2nd coefficient is the derivative of the polynomial at x = 0, and that
+        // happens to be the last timestamp that we end up passing to `poly_fit_least_squares`.
+        poly_fit_least_squares(time, values, &weights[..sample_count], degree)
+            .map(|coefficients| coefficients[1])
+            .unwrap_or_default()
     }
 }
 
-#[derive(Debug, Default)]
-pub struct VelocityTracker {
+/// The configuration of a velocity tracker.
+///
+/// The defaults match the velocity tracker of Android, which keeps 20 samples and discards
+/// the samples older than 100 ms or preceding a 40 ms pause. Input devices that report at a
+/// higher rate need more samples to cover the same horizon.
+///
+/// ```
+/// use fluid_scroll::{VelocityTracker, VelocityTrackerConfig, VelocityTrackerStrategy};
+///
+/// let config = VelocityTrackerConfig::new()
+///     .with_strategy(VelocityTrackerStrategy::Lsq2)
+///     .with_history_size(48)
+///     .with_horizon(80.0);
+/// let velocity_tracker: VelocityTracker = VelocityTracker::with_config(config);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VelocityTrackerConfig {
     strategy: Strategy,
-    samples: [Option<DataPoint>; HISTORY_SIZE],
-    index: usize,
-
-    cache: RefCell<Cache>,
+    history_size: usize,
+    horizon: f32,
+    pointer_stopped_threshold: f32,
 }
 
-impl VelocityTracker {
+impl VelocityTrackerConfig {
+    /// The maximum number of samples that a velocity tracker can keep.
+    pub const MAX_HISTORY_SIZE: usize = MAX_HISTORY_SIZE;
+
     pub fn new() -> Self {
         Self::default()
     }
 
-    pub fn with_strategy(strategy: Strategy) -> Self {
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// The maximum number of samples that the tracker keeps.
+    pub fn history_size(&self) -> usize {
+        self.history_size
+    }
+
+    /// Sets the maximum number of samples, which is clamped between 2 and `MAX_HISTORY_SIZE`.
+    pub fn with_history_size(mut self, history_size: usize) -> Self {
+        self.history_size = history_size.clamp(2, MAX_HISTORY_SIZE);
+        self
+    }
+
+    /// The age in milliseconds beyond which samples are not used to estimate the velocity.
+    pub fn horizon(&self) -> f32 {
+        self.horizon
+    }
+
+    pub fn with_horizon(mut self, horizon: f32) -> Self {
+        self.horizon = horizon.max(0.0);
+        self
+    }
+
+    /// The time gap in milliseconds between two consecutive samples beyond which the pointer
+    /// is assumed to have stopped, so the samples before the gap are not used.
+    ///
+    /// `Strategy::Recurrence` uses all samples, and estimates zero velocity instead if the
+    /// pointer has not moved for longer than this threshold by the time of the newest sample.
+    pub fn pointer_stopped_threshold(&self) -> f32 {
+        self.pointer_stopped_threshold
+    }
+
+    pub fn with_pointer_stopped_threshold(mut self, pointer_stopped_threshold: f32) -> Self {
+        self.pointer_stopped_threshold = pointer_stopped_threshold.max(0.0);
+        self
+    }
+}
+
+impl Default for VelocityTrackerConfig {
+    fn default() -> Self {
         Self {
-            strategy,
-            ..Self::default()
+            strategy: Strategy::default(),
+            history_size: HISTORY_SIZE,
+            horizon: HORIZON_MILLISECONDS,
+            pointer_stopped_threshold: ASSUME_POINTER_MOVE_STOPPED_MILLISECONDS,
         }
     }
+}
 
-    /// Adds a data point for velocity calculation at a given time.
-    pub fn add_data_point(&mut self, time_milliseconds: f32, value: f32) {
-        let data_point = DataPoint {
-            time: time_milliseconds,
-            value,
-        };
-        self.index = (self.index + 1) % HISTORY_SIZE;
-        self.samples[self.index] = Some(data_point);
+/// A ring buffer of the most recent samples, each with a value for every axis.
+#[derive(Debug)]
+struct History<T, const N: usize> {
+    config: VelocityTrackerConfig,
+    samples: [Option<DataPoint<T, N>>; MAX_HISTORY_SIZE],
+    index: usize,
+}
+
+impl<T: Float, const N: usize> History<T, N> {
+    fn with_config(config: VelocityTrackerConfig) -> Self {
+        Self {
+            config,
+            samples: [None; MAX_HISTORY_SIZE],
+            index: 0,
+        }
     }
 
-    /// Computes the estimated velocity at the time of the last provided data point.
-    pub fn calculate(&self) -> f32 {
+    fn add(&mut self, time: T, values: [T; N]) {
+        self.index = (self.index + 1) % self.config.history_size;
+        self.samples[self.index] = Some(DataPoint { time, values });
+    }
+
+    /// Computes the estimated velocity of every axis at the time of the newest sample.
+    fn calculate(&self) -> [T; N] {
+        let VelocityTrackerConfig {
+            strategy,
+            history_size,
+            horizon,
+            pointer_stopped_threshold,
+        } = self.config;
+        let horizon = T::from_f64(horizon.into());
+        let pointer_stopped_threshold = T::from_f64(pointer_stopped_threshold.into());
         let mut index = self.index;
         let mut sample_count = 0;
 
         // The sample at index is our newest sample.  If it is null, we have no samples so return.
         let Some(newest) = self.samples[index] else {
-            return 0.0;
+            return [T::zero(); N];
         };
+        // Using a recurrence relation for calculation, no sampling point can be discarded,
+        // so a pause before the release has to be recognized separately.
+        if strategy == Strategy::Recurrence && self.pointer_stopped() {
+            return [T::zero(); N];
+        }
         let mut previous = newest;
 
-        let mut cache_mut = self.cache.borrow_mut();
-
-        loop {
-            let Some(sample) = self.samples[index] else {
-                break;
-            };
+        // The samples within the horizon, from newest to oldest.
+        let mut time = [T::zero(); MAX_HISTORY_SIZE];
+        let mut values = [[T::zero(); MAX_HISTORY_SIZE]; N];
 
+        while let Some(sample) = self.samples[index] {
             let age = newest.time - sample.time;
-            // Using a recurrence relation for calculation,
-            // no sampling point can be discarded.
-            if self.strategy != Strategy::Recurrence {
-                let delta = (sample.value - previous.value).abs();
+            if strategy != Strategy::Recurrence {
+                let delta = previous.time - sample.time;
                 previous = sample;
-                if age > HORIZON_MILLISECONDS || delta > ASSUME_POINTER_MOVE_STOPPED_MILLISECONDS {
+                if age > horizon || delta > pointer_stopped_threshold {
                     break;
                 }
             }
 
-            cache_mut.reusable_values[sample_count] = sample.value;
-            cache_mut.reusable_time[sample_count] = -age;
-            index = if index == 0 { HISTORY_SIZE } else { index } - 1;
+            for (values, value) in values.iter_mut().zip(sample.values) {
+                values[sample_count] = value;
+            }
+            time[sample_count] = -age;
+            index = if index == 0 { history_size } else { index } - 1;
 
             sample_count += 1;
 
-            if sample_count >= HISTORY_SIZE {
+            if sample_count >= history_size {
                 break;
             }
         }
 
-        if sample_count >= self.min_sample_size() {
-            match self.strategy {
-                Strategy::Recurrence => calculate_recurrence_relation_velocity(
-                    cache_mut
-                        .reusable_time
-                        .iter()
-                        .take(sample_count.min(4))
-                        .rev(),
-                    cache_mut
-                        .reusable_values
-                        .iter()
-                        .take(sample_count.min(4))
-                        .rev(),
-                )
-                .ok(),
-
-                Strategy::Lsq2 =>
-                // The 2nd coefficient is the derivative of the quadratic polynomial at
-                // x = 0, and that happens to be the last timestamp that we end up
-                // passing to `poly_fit_least_squares`.
-                {
-                    poly_fit_least_squares(
-                        &cache_mut.reusable_time,
-                        &cache_mut.reusable_values,
-                        sample_count,
-                        2,
-                        [0_f32; 3],
-                    )
-                    .ok()
-                    .and_then(|r| r.get(1).cloned())
-                }
+        core::array::from_fn(|axis| {
+            strategy.estimate(&time[..sample_count], &values[axis][..sample_count])
+        })
+    }
+
+    /// Computes the estimated velocity of every axis at `time`, which may be later than the
+    /// newest sample. The velocity is zero if the pointer has not moved for longer than the
+    /// pointer stopped threshold by then.
+    fn calculate_at(&self, time: T) -> [T; N] {
+        let threshold = T::from_f64(self.config.pointer_stopped_threshold.into());
+        match self.last_moved_time() {
+            Some(moved) if time - moved > threshold => [T::zero(); N],
+            _ => self.calculate(),
+        }
+    }
+
+    /// Returns the time at which the pointer arrived at the position of the newest sample.
+    fn last_moved_time(&self) -> Option<T> {
+        let history_size = self.config.history_size;
+        let mut index = self.index;
+        let newest = self.samples[index]?;
+        let mut moved = newest.time;
+        for _ in 1..history_size {
+            index = if index == 0 { history_size } else { index } - 1;
+            match self.samples[index] {
+                Some(sample) if sample.values == newest.values => moved = sample.time,
+                _ => break,
             }
-            .unwrap_or_default()
-        } else {
-            0_f32
         }
+        Some(moved)
     }
 
-    pub fn reset(&mut self) {
+    /// Returns `true` if the pointer has not moved for longer than the pointer stopped threshold
+    /// by the time of the newest sample.
+    ///
+    /// The pointer is also assumed to have stopped if there is a gap longer than the threshold
+    /// between the newest sample and its last movement, since no samples are reported while the
+    /// pointer rests on some platforms.
+    fn pointer_stopped(&self) -> bool {
+        let history_size = self.config.history_size;
+        let threshold = T::from_f64(self.config.pointer_stopped_threshold.into());
+        let mut index = self.index;
+        let Some(newest) = self.samples[index] else {
+            return false;
+        };
+        let mut newer = newest;
+        for _ in 1..history_size {
+            index = if index == 0 { history_size } else { index } - 1;
+            let Some(sample) = self.samples[index] else {
+                break;
+            };
+            if newer.time - sample.time > threshold {
+                return true;
+            }
+            if newer.values != sample.values {
+                // The pointer moved last between these two samples.
+                return newest.time - newer.time > threshold;
+            }
+            newer = sample;
+        }
+        false
+    }
+
+    fn reset(&mut self) {
         self.samples.fill(None);
         self.index = 0;
     }
+}
 
-    pub fn approaching_halt(horizontal_velocity: f32, vertical_velocity: f32) -> bool {
-        horizontal_velocity * horizontal_velocity + vertical_velocity * vertical_velocity
-            < 0.0625_f32
+impl<T: Float, const N: usize> Default for History<T, N> {
+    fn default() -> Self {
+        Self::with_config(VelocityTrackerConfig::default())
     }
 }
 
-impl VelocityTracker {
-    fn min_sample_size(&self) -> usize {
-        match self.strategy {
-            Strategy::Recurrence => 2,
-            Strategy::Lsq2 => 3,
-        }
-    }
+#[derive(Debug, Default)]
+pub struct VelocityTracker<T: Float = f32> {
+    history: History<T, 1>,
 }
 
-#[derive(Debug, Clone)]
-struct Matrix(Vec<Vector>);
+impl<T: Float> VelocityTracker<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-impl Matrix {
-    pub fn new(rows: usize, columns: usize) -> Self {
-        let mut matrix = Vec::with_capacity(rows);
-        for _ in 0..rows {
-            matrix.push(Vector::with_capacity(columns));
+    pub fn with_strategy(strategy: Strategy) -> Self {
+        Self::with_config(VelocityTrackerConfig::new().with_strategy(strategy))
+    }
+
+    pub fn with_config(config: VelocityTrackerConfig) -> Self {
+        Self {
+            history: History::with_config(config),
         }
-        Self(matrix)
     }
-}
 
-impl Index<usize> for Matrix {
-    type Output = Vector;
+    pub fn config(&self) -> &VelocityTrackerConfig {
+        &self.history.config
+    }
+
+    /// Adds a data point for velocity calculation at a given time.
+    pub fn add_data_point(&mut self, time_milliseconds: T, value: T) {
+        self.history.add(time_milliseconds, [value]);
+    }
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.0[index]
+    /// Computes the estimated velocity at the time of the last provided data point.
+    pub fn calculate(&self) -> T {
+        let [velocity] = self.history.calculate();
+        velocity
+    }
+
+    /// Computes the estimated velocity at the given time, such as when the pointer is lifted.
+    ///
+    /// The velocity is zero if the pointer has rested for longer than the pointer stopped
+    /// threshold before `time_milliseconds`, even if no data point was added while it rested.
+    pub fn calculate_at(&self, time_milliseconds: T) -> T {
+        let [velocity] = self.history.calculate_at(time_milliseconds);
+        velocity
     }
-}
 
-impl IndexMut<usize> for Matrix {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.0[index]
+    pub fn reset(&mut self) {
+        self.history.reset();
+    }
+
+    pub fn approaching_halt(horizontal_velocity: T, vertical_velocity: T) -> bool {
+        horizontal_velocity * horizontal_velocity + vertical_velocity * vertical_velocity
+            < T::from_f64(0.0625)
     }
 }
 
-#[derive(Debug, Clone, Default)]
-struct Vector(Vec<f32>);
+/// A velocity tracker that estimates the velocity along both axes from the same samples.
+///
+/// Both axes share the sample timestamps, so the samples discarded by the horizon
+/// are always the same for the horizontal and vertical velocity.
+#[derive(Debug, Default)]
+pub struct VelocityTracker2D {
+    history: History<f32, 2>,
+}
 
-impl Vector {
-    pub fn with_capacity(capacity: usize) -> Self {
-        let mut vector = Vec::with_capacity(capacity);
-        vector.resize(capacity, 0_f32);
-        Self(vector)
+impl VelocityTracker2D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_strategy(strategy: Strategy) -> Self {
+        Self::with_config(VelocityTrackerConfig::new().with_strategy(strategy))
     }
 
-    pub fn dot(&self, other: &Self) -> f32 {
-        let mut result = 0_f32;
-        for i in 0..self.0.len() {
-            result += self.0[i] * other.0[i];
+    pub fn with_config(config: VelocityTrackerConfig) -> Self {
+        Self {
+            history: History::with_config(config),
         }
-        result
     }
 
-    pub fn norm(&self) -> f32 {
-        self.dot(self).sqrt()
+    pub fn config(&self) -> &VelocityTrackerConfig {
+        &self.history.config
+    }
+
+    /// Adds a data point for velocity calculation at a given time.
+    pub fn add_data_point(&mut self, time_milliseconds: f32, position: Point) {
+        self.history
+            .add(time_milliseconds, [position.x, position.y]);
     }
-}
 
-impl Deref for Vector {
-    type Target = Vec<f32>;
+    /// Computes the estimated velocity vector at the time of the last provided data point.
+    pub fn calculate(&self) -> Point {
+        let [x, y] = self.history.calculate();
+        Point::new(x, y)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Computes the estimated velocity vector at the given time, such as when the pointer
+    /// is lifted. See [`VelocityTracker::calculate_at`] for details.
+    pub fn calculate_at(&self, time_milliseconds: f32) -> Point {
+        let [x, y] = self.history.calculate_at(time_milliseconds);
+        Point::new(x, y)
     }
-}
 
-impl Index<usize> for Vector {
-    type Output = f32;
+    pub fn reset(&mut self) {
+        self.history.reset();
+    }
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.0[index]
+    /// Returns `true` if the velocity is too small to start an inertial scroll.
+    pub fn approaching_halt(velocity: Point) -> bool {
+        VelocityTracker::approaching_halt(velocity.x, velocity.y)
     }
 }
 
-impl IndexMut<usize> for Vector {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.0[index]
-    }
+fn dot<T: Float>(a: &[T], b: &[T]) -> T {
+    a.iter().zip(b).map(|(&a, &b)| a * b).sum()
 }
 
-/// Fits a polynomial of the given degree to the data points.
-fn poly_fit_least_squares(
-    x: &Vector,
-    y: &Vector,
-    sample_count: usize,
+fn norm<T: Float>(a: &[T]) -> T {
+    dot(a, a).sqrt()
+}
+
+/// The highest degree of polynomial that `poly_fit_least_squares` can fit.
+const MAX_DEGREE: usize = 3;
+
+/// Fits a polynomial of the given degree to the data points, weighing each point by `w`.
+fn poly_fit_least_squares<T: Float>(
+    x: &[T],
+    y: &[T],
+    w: &[T],
     degree: usize,
-    mut coefficients: [f32; 3],
-) -> Result<[f32; 3], &'static str> {
+) -> Result<[T; MAX_DEGREE + 1], &'static str> {
+    let sample_count = x.len();
     if degree < 1 {
         return Err("The degree must be at positive integer");
     }
+    if degree > MAX_DEGREE {
+        return Err("The degree must not be greater than 3");
+    }
     if sample_count == 0 {
         return Err("At least one point must be provided");
     }
+    if sample_count > MAX_HISTORY_SIZE || y.len() != sample_count || w.len() != sample_count {
+        return Err("The number of times, values and weights must be equal");
+    }
 
     let truncated_degree = if degree >= sample_count {
         sample_count - 1
@@ -292,9 +567,9 @@ fn poly_fit_least_squares(
     let n = truncated_degree + 1;
 
     // Expand the X vector to a matrix A, pre-multiplied by the weights.
-    let mut a = Matrix::new(n, m);
+    let mut a = [[T::zero(); MAX_HISTORY_SIZE]; MAX_DEGREE + 1];
     for h in 0..m {
-        a[0][h] = 1_f32;
+        a[0][h] = w[h];
         for i in 1..n {
             a[i][h] = a[i - 1][h] * x[h];
         }
@@ -303,42 +578,48 @@ fn poly_fit_least_squares(
     // Apply the Gram-Schmidt process to A to obtain its QR decomposition.
 
     // Orthonormal basis, column-major order.
-    let mut q = Matrix::new(n, m);
+    let mut q = [[T::zero(); MAX_HISTORY_SIZE]; MAX_DEGREE + 1];
     // Upper triangular matrix, row-major order.
-    let mut r = Matrix::new(n, n);
+    let mut r = [[T::zero(); MAX_DEGREE + 1]; MAX_DEGREE + 1];
     for j in 0..n {
-        let aw = &mut a[j];
-        for h in 0..m {
-            q[j][h] = aw[h];
-        }
-        for i in 0..j {
-            let dot = q[j].dot(&q[i]);
-            for h in 0..m {
-                q[j][h] -= dot * q[i][h];
+        let (bases, rest) = q.split_at_mut(j);
+        let column = &mut rest[0][..m];
+        column.copy_from_slice(&a[j][..m]);
+        for basis in bases.iter() {
+            let dot = dot(column, &basis[..m]);
+            for (value, &basis) in column.iter_mut().zip(basis) {
+                *value -= dot * basis;
             }
         }
 
-        let norm = q[j].norm();
-        if norm < 1e-6 {
+        let norm = norm(column);
+        if norm < T::from_f64(1e-6) {
             return Err("Vectors are linearly dependent or zero so no solution.");
         }
 
-        let inverse_norm = 1_f32 / norm;
-        for h in 0..m {
-            q[j][h] *= inverse_norm;
+        let inverse_norm = T::one() / norm;
+        for value in column.iter_mut() {
+            *value *= inverse_norm;
         }
-        let v = &mut r[j];
         for i in 0..n {
-            v[i] = if i < j { 0_f32 } else { q[j].dot(&a[i]) };
+            r[j][i] = if i < j {
+                T::zero()
+            } else {
+                dot(&q[j][..m], &a[i][..m])
+            };
         }
     }
 
     // Solve R B = Qt W Y to find B. This is easy because R is upper triangular.
     // We just work from bottom-right to top-left calculating B's coefficients.
-    let wy = y;
+    let mut wy = [T::zero(); MAX_HISTORY_SIZE];
+    for h in 0..m {
+        wy[h] = y[h] * w[h];
+    }
 
+    let mut coefficients = [T::zero(); MAX_DEGREE + 1];
     for i in (0..=n - 1).rev() {
-        coefficients[i] = q[i].dot(wy);
+        coefficients[i] = dot(&q[i][..m], &wy[..m]);
         for j in (i + 1..=n - 1).rev() {
             coefficients[i] -= r[i][j] * coefficients[j];
         }
@@ -348,58 +629,99 @@ fn poly_fit_least_squares(
     Ok(coefficients)
 }
 
-fn calculate_recurrence_relation_velocity<'a, T, V>(
-    times_iter: T,
-    values_iter: V,
-) -> Result<f32, &'static str>
-where
-    T: ExactSizeIterator<Item = &'a f32>,
-    V: ExactSizeIterator<Item = &'a f32>,
-{
-    if times_iter.len() != values_iter.len() {
+/// Converts the kinetic energy of a unit mass to the velocity that it moves with,
+/// keeping the sign of the energy as the direction.
+fn kinetic_energy_to_velocity<T: Float>(work: T) -> T {
+    work.signum() * work.abs().sqrt() * T::SQRT_2()
+}
+
+/// Calculates the velocity by accumulating the kinetic energy that each movement imparts
+/// to a unit mass, as the `Impulse` strategy of the Android Open Source Project does.
+///
+/// The samples are ordered from newest to oldest.
+fn calculate_impulse_velocity<T: Float>(t: &[T], x: &[T]) -> T {
+    let sample_count = t.len().min(x.len());
+    if sample_count < 2 || t[1] > t[0] {
+        return T::zero();
+    }
+    if sample_count == 2 {
+        if t[1] == t[0] {
+            return T::zero();
+        }
+        return (x[1] - x[0]) / (t[1] - t[0]);
+    }
+
+    let mut work = T::zero();
+    // Starts with the oldest sample and goes forward in time.
+    for i in (1..sample_count).rev() {
+        if t[i] == t[i - 1] {
+            // The two points are at the same time, so we can't calculate a velocity.
+            continue;
+        }
+        let previous_velocity = kinetic_energy_to_velocity(work);
+        let current_velocity = (x[i] - x[i - 1]) / (t[i] - t[i - 1]);
+        work += (current_velocity - previous_velocity) * current_velocity.abs();
+        if i == sample_count - 1 {
+            // The initial velocity is not known, so only half of the energy is imparted.
+            work *= T::from_f64(0.5);
+        }
+    }
+    kinetic_energy_to_velocity(work)
+}
+
+/// Calculates the velocity with a recurrence relation of the velocities between
+/// consecutive points, ordered from oldest to newest.
+fn calculate_recurrence_relation_velocity<T: Float>(
+    times: &[T],
+    values: &[T],
+) -> Result<T, &'static str> {
+    if times.len() != values.len() {
         return Err("The number of times and values must be equal");
     }
 
-    let sample_count = times_iter.len();
+    let sample_count = times.len();
     if sample_count < 2 {
         return Err("At least two points must be provided");
     }
+    if sample_count > RECURRENCE_SAMPLE_SIZE {
+        return Err("Too many points are provided");
+    }
 
-    let points = values_iter.zip(times_iter).collect::<Vec<_>>();
-    let samples = points
-        .windows(2)
-        .filter_map(|window| {
-            let delta_time = window[1].1 - window[0].1;
-            if delta_time == 0_f32 {
-                // The two points are at the same time, so we can't calculate a velocity.
-                // Discard this sample.
-                None
-            } else {
-                Some((window[1].0 - window[0].0) / delta_time)
-            }
-        })
-        .collect::<Vec<_>>();
+    let mut samples = [T::zero(); RECURRENCE_SAMPLE_SIZE - 1];
+    let mut velocity_count = 0;
+    for i in 1..sample_count {
+        let delta_time = times[i] - times[i - 1];
+        if delta_time == T::zero() {
+            // The two points are at the same time, so we can't calculate a velocity.
+            // Discard this sample.
+            continue;
+        }
+        samples[velocity_count] = (values[i] - values[i - 1]) / delta_time;
+        velocity_count += 1;
+    }
+    let samples = &samples[..velocity_count];
 
     // Save the velocity values of the last two times.
     let mut previous_velocity = None;
     let mut current_velocity = None;
     samples.windows(2).for_each(|window| {
-        let velocity = window[0] * 0.4 + window[1] * 0.6;
+        let velocity = window[0] * T::from_f64(0.4) + window[1] * T::from_f64(0.6);
         if let Some(current) = current_velocity {
             previous_velocity = Some(current);
             // Weighted average of the velocity with a ratio of 8:2 compared to the previous time.
-            current_velocity = Some(current * 0.8 + velocity * 0.2);
+            current_velocity = Some(current * T::from_f64(0.8) + velocity * T::from_f64(0.2));
         } else {
             current_velocity = Some(velocity);
         }
     });
     let Some(current) = current_velocity else {
-        return Ok(*samples
+        return samples
             .first()
-            .expect("At least one velocity sampling is required"));
+            .copied()
+            .ok_or("At least one velocity sampling is required");
     };
     if let Some(previous) = previous_velocity {
-        Ok(previous * 0.75 + current * 0.25)
+        Ok(previous * T::from_f64(0.75) + current * T::from_f64(0.25))
     } else {
         Ok(current)
     }
@@ -407,9 +729,9 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::velocity_tracker::Strategy;
+    use crate::{geometry::Point, velocity_tracker::Strategy};
 
-    use super::VelocityTracker;
+    use super::{VelocityTracker, VelocityTracker2D, VelocityTrackerConfig};
 
     #[test]
     fn test_lsq2() {
@@ -422,6 +744,92 @@ mod tests {
         assert!((velocity - 0.55).abs() < 0.001)
     }
 
+    #[test]
+    fn test_constant_velocity() {
+        let strategies = [
+            Strategy::Recurrence,
+            Strategy::Lsq1,
+            Strategy::Lsq2,
+            Strategy::Lsq3,
+            Strategy::Wlsq2Delta,
+            Strategy::Wlsq2Central,
+            Strategy::Wlsq2Recent,
+            Strategy::Impulse,
+        ];
+        for strategy in strategies {
+            let mut velocity_tracker = VelocityTracker::with_strategy(strategy);
+            for i in 0..10 {
+                let time = i as f32 * 8_f32;
+                velocity_tracker.add_data_point(time, time * 0.5);
+            }
+            let velocity = velocity_tracker.calculate();
+            assert!(
+                (velocity - 0.5).abs() < 0.001,
+                "{:?}: {}",
+                strategy,
+                velocity
+            );
+        }
+    }
+
+    #[test]
+    fn test_config() {
+        let samples = [
+            (0.0, 0.0),
+            (10.0, 100.0),
+            (20.0, 200.0),
+            (80.0, 201.0),
+            (90.0, 202.0),
+            (100.0, 203.0),
+        ];
+        let track = |config| {
+            let mut velocity_tracker = VelocityTracker::<f32>::with_config(config);
+            for (time, value) in samples {
+                velocity_tracker.add_data_point(time, value);
+            }
+            velocity_tracker.calculate()
+        };
+
+        // The samples before the 60 ms pause are discarded.
+        let config = VelocityTrackerConfig::new().with_strategy(Strategy::Lsq1);
+        assert!((track(config) - 0.1).abs() < 1e-3);
+        // The samples before the pause are used with a longer threshold.
+        let config = config.with_pointer_stopped_threshold(80.0);
+        assert!(track(config) > 1.0);
+        // Only the samples that the history can hold are used.
+        let config = config.with_history_size(3);
+        assert!((track(config) - 0.1).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_recurrence_pause() {
+        let mut velocity_tracker = VelocityTracker::new();
+        for i in 0..5 {
+            let time = i as f32 * 16_f32;
+            velocity_tracker.add_data_point(time, time);
+        }
+        assert!(velocity_tracker.calculate() > 0.5);
+
+        // The pointer rests without reporting any sample, then lifts.
+        velocity_tracker.add_data_point(564_f32, 64_f32);
+        assert_eq!(velocity_tracker.calculate(), 0_f32);
+
+        // The pointer keeps reporting the same position while resting.
+        velocity_tracker.reset();
+        for i in 0..40 {
+            let time = i as f32 * 16_f32;
+            velocity_tracker.add_data_point(time, time.min(64_f32));
+        }
+        assert_eq!(velocity_tracker.calculate(), 0_f32);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<VelocityTracker>();
+        assert_send_sync::<VelocityTracker2D>();
+    }
+
     #[test]
     fn test_instantaneous() {
         let mut velocity_tracker = VelocityTracker::with_strategy(Strategy::Recurrence);
@@ -431,6 +839,33 @@ mod tests {
         velocity_tracker.add_data_point(22.2795_f32, 153.5_f32);
         velocity_tracker.add_data_point(58.22_f32, 151.5_f32);
         let velocity = velocity_tracker.calculate();
-        assert!((velocity + 6.678).abs() < 0.001)
+        // The 4 newest samples move at -49.951, -7.205 and -0.056 points per millisecond.
+        // The pairs are blended 4:6 into -24.303 and -2.915, smoothed 8:2 into -20.026,
+        // and the last two estimates are combined 3:1 into -23.234.
+        assert!((velocity + 23.234).abs() < 0.001)
+    }
+
+    #[test]
+    fn test_2d_matches_1d() {
+        let samples = [
+            (0.0, 0.0, 0.0),
+            (10.0, 20.0, -5.0),
+            (20.0, 30.0, -15.0),
+            (30.0, 40.0, -30.0),
+        ];
+        for strategy in [Strategy::Recurrence, Strategy::Lsq2] {
+            let mut x = VelocityTracker::with_strategy(strategy);
+            let mut y = VelocityTracker::with_strategy(strategy);
+            let mut tracker = VelocityTracker2D::with_strategy(strategy);
+            for (time, px, py) in samples {
+                x.add_data_point(time, px);
+                y.add_data_point(time, py);
+                tracker.add_data_point(time, Point::new(px, py));
+            }
+            assert_eq!(
+                tracker.calculate(),
+                Point::new(x.calculate(), y.calculate())
+            );
+        }
     }
 }
@@ -0,0 +1,444 @@
+// Copyright 2023 ktiays
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::f32::consts::LN_2;
+
+use crate::{
+    geometry::Point,
+    math::{exp, ln},
+    rubber_band::RubberBand,
+    spring_back::{valid_response, SpringBack, DEFAULT_RESPONSE},
+    velocity_tracker::VelocityTracker,
+};
+
+// The zoom is simulated on the logarithm of the scale, so that zooming in by a factor feels
+// the same as zooming out by it. The logarithm is expressed in thousandths, which keeps the
+// rest thresholds of `SpringBack` well below a visible change of the scale.
+const LOG_SCALE_UNIT: f32 = 1000.0;
+
+// The range that the rubber band is applied over, in log scale units. The reciprocal curve
+// only approaches the range, so the scale stays within a factor of 2 of a limit whatever
+// the coefficient is.
+const RUBBER_BAND_RANGE: f32 = LN_2 * LOG_SCALE_UNIT;
+
+/// The phases that a zoom controller goes through during a zoom interaction.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ZoomPhase {
+    /// The scale is at rest.
+    #[default]
+    Idle = 0,
+    /// The user is pinching the content.
+    Pinching = 1,
+    /// The scale springs back to the zoom limit that it was pinched beyond.
+    Bouncing = 2,
+    /// The scale moves to a value requested by `animate_to` or `double_tap`.
+    Animating = 3,
+}
+
+/// A headless state machine that simulates pinch-to-zoom, like the zooming of `UIScrollView`.
+///
+/// The scale is limited by a minimum and a maximum zoom scale. Pinching beyond the limits is
+/// resisted by a rubber band, and the scale springs back to the limit when the pinch ends.
+/// The scale is meant to be applied around `focal_point`, for example with
+/// [`ScrollController2D::scale_content`](crate::ScrollController2D::scale_content).
+#[derive(Debug)]
+pub struct ZoomController {
+    min_scale: f32,
+    max_scale: f32,
+    bounces_zoom: bool,
+    bounce_response: f32,
+    rubber_band: RubberBand<f32>,
+
+    scale: f32,
+    focal_point: Point,
+    phase: ZoomPhase,
+
+    pinch_began_time: f32,
+    // The logarithm of the scale when the pinch began, before the rubber band is applied.
+    pinch_began_log_scale: f32,
+    animation_began_time: f32,
+    // The logarithm of the scale that the spring moves toward.
+    spring_target: f32,
+    spring_back: SpringBack,
+    velocity_tracker: VelocityTracker,
+}
+
+impl ZoomController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The minimum scale factor that can be applied to the content.
+    pub fn min_scale(&self) -> f32 {
+        self.min_scale
+    }
+
+    pub fn set_min_scale(&mut self, min_scale: f32) {
+        self.min_scale = min_scale.max(f32::MIN_POSITIVE);
+        self.clamp_scale_at_rest();
+    }
+
+    /// The maximum scale factor that can be applied to the content.
+    ///
+    /// The value is never less than the minimum scale.
+    pub fn max_scale(&self) -> f32 {
+        self.max_scale.max(self.min_scale)
+    }
+
+    pub fn set_max_scale(&mut self, max_scale: f32) {
+        self.max_scale = max_scale;
+        self.clamp_scale_at_rest();
+    }
+
+    /// Whether the scale can be pinched beyond its limits and springs back afterwards.
+    pub fn bounces_zoom(&self) -> bool {
+        self.bounces_zoom
+    }
+
+    pub fn set_bounces_zoom(&mut self, bounces_zoom: bool) {
+        self.bounces_zoom = bounces_zoom;
+    }
+
+    /// Sets the response of the spring that bounces the scale back to its limits.
+    pub fn set_bounce_response(&mut self, bounce_response: f32) {
+        self.bounce_response = valid_response(bounce_response);
+    }
+
+    /// The rubber band that resists pinching the scale beyond its limits.
+    pub fn rubber_band(&self) -> &RubberBand<f32> {
+        &self.rubber_band
+    }
+
+    pub fn set_rubber_band(&mut self, rubber_band: RubberBand<f32>) {
+        self.rubber_band = rubber_band;
+    }
+
+    /// The current scale factor of the content.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Changes the scale immediately, clamped to the limits, and stops any running animation.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.stop_animation();
+        self.scale = scale.clamp(self.min_scale, self.max_scale());
+    }
+
+    /// The point in the viewport that stays in place while the content is zoomed.
+    pub fn focal_point(&self) -> Point {
+        self.focal_point
+    }
+
+    pub fn phase(&self) -> ZoomPhase {
+        self.phase
+    }
+
+    /// Tells the controller that a pinch began around the given focal point.
+    ///
+    /// Any running animation is interrupted and the scale stays where it is.
+    pub fn pinch_began(&mut self, time: f32, focal_point: Point) {
+        self.stop_animation();
+        self.pinch_began_time = time;
+        self.pinch_began_log_scale = self.apply_rubber_band(log_scale(self.scale), true);
+        self.focal_point = focal_point;
+        self.velocity_tracker.reset();
+        self.velocity_tracker
+            .add_data_point(0.0, self.pinch_began_log_scale);
+        self.phase = ZoomPhase::Pinching;
+    }
+
+    /// Tells the controller that the pinch changed, where `pinch_scale` is the scale of
+    /// the gesture relative to when it began, like `scale` of `UIPinchGestureRecognizer`.
+    pub fn pinch_changed(&mut self, time: f32, pinch_scale: f32, focal_point: Point) {
+        if self.phase != ZoomPhase::Pinching || pinch_scale <= 0.0 {
+            return;
+        }
+        let pulled = self.pinch_began_log_scale + log_scale(pinch_scale);
+        self.velocity_tracker
+            .add_data_point(time - self.pinch_began_time, pulled);
+        self.scale = exp_scale(self.apply_rubber_band(pulled, false));
+        self.focal_point = focal_point;
+    }
+
+    /// Tells the controller that the pinch ended at the given time.
+    ///
+    /// If the scale is beyond its limits, it springs back to the nearest one with the velocity
+    /// of the gesture. Otherwise the scale stays where it is.
+    pub fn pinch_ended(&mut self, time: f32) {
+        if self.phase != ZoomPhase::Pinching {
+            return;
+        }
+        let log = log_scale(self.scale);
+        let overflow = self.overflow(log);
+        if overflow == 0.0 {
+            self.phase = ZoomPhase::Idle;
+            return;
+        }
+        let limit = log - overflow;
+        let pulled = self.apply_rubber_band(log, true) - limit;
+        // Converts the velocity of the fingers to the velocity of the rubber banded scale.
+        let velocity = self
+            .velocity_tracker
+            .calculate_at(time - self.pinch_began_time)
+            * self
+                .rubber_band
+                .calculate_derivative(pulled, RUBBER_BAND_RANGE);
+        self.begin_spring(time, velocity, limit, self.bounce_response);
+        self.phase = ZoomPhase::Bouncing;
+    }
+
+    /// Animates the scale to the given value around the given focal point.
+    pub fn animate_to(&mut self, now: f32, scale: f32, focal_point: Point) {
+        self.stop_animation();
+        self.focal_point = focal_point;
+        let target = log_scale(scale.clamp(self.min_scale, self.max_scale()));
+        self.begin_spring(now, 0.0, target, DEFAULT_RESPONSE);
+        self.phase = ZoomPhase::Animating;
+    }
+
+    /// Zooms in to the maximum scale around the tapped point, or back out to the minimum scale
+    /// if the content is already zoomed in.
+    pub fn double_tap(&mut self, now: f32, focal_point: Point) {
+        let scale = if self.scale > self.min_scale * (1.0 + f32::EPSILON) {
+            self.min_scale
+        } else {
+            self.max_scale()
+        };
+        self.animate_to(now, scale, focal_point);
+    }
+
+    /// Advances the animation to the given time and returns the scale at that moment.
+    pub fn tick(&mut self, now: f32) -> f32 {
+        if matches!(self.phase, ZoomPhase::Bouncing | ZoomPhase::Animating) {
+            match self.spring_back.value(now - self.animation_began_time) {
+                Some(value) => self.scale = exp_scale(self.spring_target + value.offset),
+                None => {
+                    self.scale = exp_scale(self.spring_target);
+                    self.stop_animation();
+                }
+            }
+        }
+        self.scale
+    }
+}
+
+impl ZoomController {
+    fn stop_animation(&mut self) {
+        self.spring_back.reset();
+        self.phase = ZoomPhase::Idle;
+    }
+
+    fn clamp_scale_at_rest(&mut self) {
+        if self.phase == ZoomPhase::Idle {
+            self.scale = self.scale.clamp(self.min_scale, self.max_scale());
+        }
+    }
+
+    fn begin_spring(&mut self, time: f32, velocity: f32, target: f32, response: f32) {
+        self.spring_target = target;
+        self.spring_back.reset();
+        self.spring_back
+            .absorb_with_response(velocity, log_scale(self.scale) - target, response);
+        self.animation_began_time = time;
+    }
+
+    /// The distance in log scale units by which `log` exceeds the limits.
+    fn overflow(&self, log: f32) -> f32 {
+        let min = log_scale(self.min_scale);
+        let max = log_scale(self.max_scale());
+        if log < min {
+            log - min
+        } else if log > max {
+            log - max
+        } else {
+            0.0
+        }
+    }
+
+    fn apply_rubber_band(&self, log: f32, inverse: bool) -> f32 {
+        let overflow = self.overflow(log);
+        if overflow == 0.0 {
+            return log;
+        }
+        let limit = log - overflow;
+        if !self.bounces_zoom {
+            return limit;
+        }
+        let transformed = if inverse {
+            self.rubber_band
+                .calculate_offset_inv(overflow.abs(), RUBBER_BAND_RANGE)
+        } else {
+            self.rubber_band
+                .calculate_offset(overflow.abs(), RUBBER_BAND_RANGE)
+        };
+        limit + transformed * overflow.signum()
+    }
+}
+
+impl Default for ZoomController {
+    fn default() -> Self {
+        Self {
+            min_scale: 1.0,
+            max_scale: 1.0,
+            bounces_zoom: true,
+            bounce_response: DEFAULT_RESPONSE,
+            rubber_band: RubberBand::default(),
+            scale: 1.0,
+            focal_point: Point::ZERO,
+            phase: ZoomPhase::Idle,
+            pinch_began_time: 0.0,
+            pinch_began_log_scale: 0.0,
+            animation_began_time: 0.0,
+            spring_target: 0.0,
+            spring_back: SpringBack::new(),
+            velocity_tracker: VelocityTracker::new(),
+        }
+    }
+}
+
+fn log_scale(scale: f32) -> f32 {
+    ln(scale) * LOG_SCALE_UNIT
+}
+
+fn exp_scale(log: f32) -> f32 {
+    exp(log / LOG_SCALE_UNIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        geometry::{Point, Size},
+        rubber_band::RubberBand,
+        ScrollController2D,
+    };
+
+    use super::{ZoomController, ZoomPhase};
+
+    fn zoom_controller() -> ZoomController {
+        let mut zoom = ZoomController::new();
+        zoom.set_min_scale(1.0);
+        zoom.set_max_scale(4.0);
+        zoom
+    }
+
+    fn settle(zoom: &mut ZoomController, mut now: f32) -> f32 {
+        while zoom.phase() != ZoomPhase::Idle {
+            now += 16.0;
+            zoom.tick(now);
+        }
+        now
+    }
+
+    #[test]
+    fn test_pinch_is_rubber_banded() {
+        let mut zoom = zoom_controller();
+        let focal_point = Point::new(100.0, 200.0);
+        zoom.pinch_began(0.0, focal_point);
+        for i in 1..=10 {
+            zoom.pinch_changed(i as f32 * 16.0, 1.0 + i as f32 * 0.2, focal_point);
+        }
+        assert!((zoom.scale() - 3.0).abs() < 1e-4);
+        zoom.pinch_ended(160.0);
+        assert_eq!(zoom.phase(), ZoomPhase::Idle);
+
+        // Pinching far beyond the maximum scale only overshoots it a little.
+        zoom.pinch_began(200.0, focal_point);
+        zoom.pinch_changed(216.0, 4.0, focal_point);
+        assert!(zoom.scale() > 4.0 && zoom.scale() < 4.0 * 1.5);
+        zoom.pinch_ended(232.0);
+        assert_eq!(zoom.phase(), ZoomPhase::Bouncing);
+        settle(&mut zoom, 232.0);
+        assert!((zoom.scale() - 4.0).abs() < 1e-4);
+
+        // Pinching again continues from the rubber banded scale without a jump.
+        zoom.pinch_began(1000.0, focal_point);
+        zoom.pinch_changed(1016.0, 0.1, focal_point);
+        let scale = zoom.scale();
+        assert!(scale < 1.0);
+        zoom.pinch_ended(1032.0);
+        zoom.pinch_began(1040.0, focal_point);
+        zoom.pinch_changed(1056.0, 1.0, focal_point);
+        assert!((zoom.scale() - scale).abs() < 1e-3);
+
+        // The overshoot stays within a factor of 2 even with a loose rubber band.
+        zoom.set_rubber_band(RubberBand::with_coefficient(5.0));
+        zoom.pinch_changed(1072.0, 1e6, focal_point);
+        assert!(zoom.scale() > 4.0 && zoom.scale() < 8.0);
+    }
+
+    #[test]
+    fn test_hold_before_release_does_not_fling() {
+        let mut zoom = zoom_controller();
+        let focal_point = Point::new(100.0, 200.0);
+        let release_velocity =
+            |zoom: &ZoomController| zoom.spring_back.value(0.0).unwrap().velocity;
+
+        zoom.pinch_began(0.0, focal_point);
+        for i in 1..=5 {
+            zoom.pinch_changed(i as f32 * 16.0, 1.0 + i as f32, focal_point);
+        }
+        zoom.pinch_ended(80.0);
+        assert_eq!(zoom.phase(), ZoomPhase::Bouncing);
+        assert!(release_velocity(&zoom) != 0.0);
+
+        // The fingers rest beyond the maximum scale before they are lifted.
+        zoom.pinch_began(1000.0, focal_point);
+        for i in 1..=5 {
+            zoom.pinch_changed(1000.0 + i as f32 * 16.0, 1.0 + i as f32 * 0.2, focal_point);
+        }
+        zoom.pinch_ended(1500.0);
+        assert_eq!(zoom.phase(), ZoomPhase::Bouncing);
+        assert_eq!(release_velocity(&zoom), 0.0);
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let mut zoom = zoom_controller();
+        let focal_point = Point::new(100.0, 200.0);
+        zoom.double_tap(0.0, focal_point);
+        assert_eq!(zoom.phase(), ZoomPhase::Animating);
+        assert_eq!(zoom.focal_point(), focal_point);
+        let now = settle(&mut zoom, 0.0);
+        assert!((zoom.scale() - 4.0).abs() < 1e-4);
+
+        zoom.double_tap(now, Point::ZERO);
+        settle(&mut zoom, now);
+        assert!((zoom.scale() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_focal_point_stays_in_place() {
+        let mut scroll = ScrollController2D::new();
+        scroll.set_content_size(Size::new(1000.0, 1000.0));
+        scroll.set_viewport_size(Size::new(400.0, 600.0));
+        scroll.set_offset(Point::new(100.0, 200.0));
+
+        let mut zoom = zoom_controller();
+        let focal_point = Point::new(200.0, 300.0);
+        zoom.double_tap(0.0, focal_point);
+        let mut now = 0.0;
+        while zoom.phase() != ZoomPhase::Idle {
+            let previous = zoom.scale();
+            now += 16.0;
+            zoom.tick(now);
+            scroll.scale_content(zoom.scale() / previous, zoom.focal_point());
+        }
+        assert!((scroll.content_size().width - 4000.0).abs() < 0.5);
+        // The content at (300, 500) is still under the focal point.
+        let offset = scroll.offset();
+        assert!((offset.x - (300.0 * 4.0 - 200.0)).abs() < 0.5);
+        assert!((offset.y - (500.0 * 4.0 - 300.0)).abs() < 0.5);
+    }
+}